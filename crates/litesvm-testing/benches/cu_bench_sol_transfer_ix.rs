@@ -1,6 +1,7 @@
 use litesvm::LiteSVM;
 use litesvm_testing::cu_bench::{benchmark_instruction, InstructionBenchmark};
 use litesvm_testing::prelude::*;
+use litesvm_testing::seed::{seeded_keypair, test_seed};
 use log::info;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
@@ -17,9 +18,12 @@ struct SolTransferBenchmark {
 
 impl SolTransferBenchmark {
     fn new() -> Self {
+        // Seeded keypairs keep the pubkeys in the JSON output stable between runs
+        let seed = test_seed();
+
         Self {
-            sender: Keypair::new(),
-            recipient: Keypair::new(),
+            sender: seeded_keypair(seed, "sender"),
+            recipient: seeded_keypair(seed, "recipient"),
             transfer_amount: 500_000, // Smaller transfer amount for multiple measurements
         }
     }
@@ -56,15 +60,10 @@ impl InstructionBenchmark for SolTransferBenchmark {
         unsigned_tx
     }
 
-    fn address_book(&self) -> std::collections::HashMap<Pubkey, String> {
-        let mut book = std::collections::HashMap::new();
-        book.insert(
-            solana_system_interface::program::ID,
-            "system_program".to_string(),
-        );
-        book.insert(self.sender.pubkey(), "sender".to_string());
-        book.insert(self.recipient.pubkey(), "recipient".to_string());
-        book
+    fn address_book(&self) -> AddressBook {
+        AddressBook::new()
+            .with(self.sender.pubkey(), "sender")
+            .with(self.recipient.pubkey(), "recipient")
     }
 }
 
@@ -1,18 +1,14 @@
-use std::collections::HashMap;
-
 use litesvm::LiteSVM;
 use litesvm_testing::cu_bench::{benchmark_instruction, InstructionBenchmark};
 use litesvm_testing::prelude::*;
+use litesvm_testing::seed::{seeded_keypair, test_seed};
+use litesvm_testing::token_testing::{create_ata, create_mint_at, funded_token_account};
 use log::info;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
-use solana_system_interface::instruction::create_account;
 use solana_transaction::Transaction;
-use spl_associated_token_account::instruction::create_associated_token_account;
-use spl_token::instruction::{initialize_mint, mint_to};
-use spl_token::solana_program::program_pack::Pack;
 
 /// SPL token transfer benchmark using the new framework
 struct SplTokenTransferBenchmark {
@@ -27,9 +23,11 @@ struct SplTokenTransferBenchmark {
 
 impl SplTokenTransferBenchmark {
     fn new() -> Self {
-        let sender = Keypair::new();
-        let recipient = Keypair::new();
-        let mint = Keypair::new();
+        // Seeded keypairs keep the pubkeys in the JSON output stable between runs
+        let seed = test_seed();
+        let sender = seeded_keypair(seed, "sender");
+        let recipient = seeded_keypair(seed, "recipient");
+        let mint = seeded_keypair(seed, "mint");
 
         let sender_ata = spl_associated_token_account::get_associated_token_address(
             &sender.pubkey(),
@@ -42,7 +40,7 @@ impl SplTokenTransferBenchmark {
         );
 
         Self {
-            mint_authority: Keypair::new(),
+            mint_authority: seeded_keypair(seed, "mint_authority"),
             mint,
             sender,
             recipient,
@@ -61,72 +59,23 @@ impl InstructionBenchmark for SplTokenTransferBenchmark {
     fn setup_svm(&self) -> LiteSVM {
         let mut svm = LiteSVM::new();
 
-        // Airdrop to accounts that need SOL
-        svm.airdrop(&self.mint_authority.pubkey(), 50_000_000)
-            .unwrap();
+        // Airdrop to the signer that pays for the measured transfers
         svm.airdrop(&self.sender.pubkey(), 10_000_000).unwrap();
-        svm.airdrop(&self.recipient.pubkey(), 10_000_000).unwrap();
-
-        // Create mint account
-        let create_mint_account_ix = create_account(
-            &self.mint_authority.pubkey(),
-            &self.mint.pubkey(),
-            5_000_000, // Rent exemption
-            spl_token::state::Mint::LEN as u64,
-            &spl_token::ID,
-        );
 
-        // Initialize mint
-        let create_mint_ix = initialize_mint(
-            &spl_token::ID,
+        // Mint with 6 decimals, the sender holding enough for 200+ transfers
+        create_mint_at(
+            &mut svm,
             &self.mint.pubkey(),
             &self.mint_authority.pubkey(),
-            None, // No freeze authority
-            6,    // 6 decimals
-        )
-        .unwrap();
-
-        // Create associated token accounts
-        let create_sender_ata_ix = create_associated_token_account(
-            &self.mint_authority.pubkey(),
-            &self.sender.pubkey(),
-            &self.mint.pubkey(),
-            &spl_token::ID,
+            6,
         );
-
-        let create_recipient_ata_ix = create_associated_token_account(
-            &self.mint_authority.pubkey(),
-            &self.recipient.pubkey(),
-            &self.mint.pubkey(),
-            &spl_token::ID,
-        );
-
-        // Mint tokens to sender (enough for 200+ transfers)
-        let mint_to_ix = mint_to(
-            &spl_token::ID,
+        funded_token_account(
+            &mut svm,
+            &self.sender.pubkey(),
             &self.mint.pubkey(),
-            &self.sender_ata,
-            &self.mint_authority.pubkey(),
-            &[],
             50_000_000, // 50 tokens (with 6 decimals)
-        )
-        .unwrap();
-
-        // Execute setup transaction
-        let setup_tx = Transaction::new_signed_with_payer(
-            &[
-                create_mint_account_ix,
-                create_mint_ix,
-                create_sender_ata_ix,
-                create_recipient_ata_ix,
-                mint_to_ix,
-            ],
-            Some(&self.mint_authority.pubkey()),
-            &[&self.mint_authority, &self.mint],
-            svm.latest_blockhash(),
         );
-
-        svm.send_transaction(setup_tx).unwrap();
+        create_ata(&mut svm, &self.recipient.pubkey(), &self.mint.pubkey());
 
         svm
     }
@@ -152,24 +101,15 @@ impl InstructionBenchmark for SplTokenTransferBenchmark {
         unsigned_tx
     }
 
-    fn address_book(&self) -> HashMap<Pubkey, String> {
-        HashMap::from_iter(vec![
-            (spl_token::ID, "spl_token".to_string()),
-            (
-                spl_associated_token_account::ID,
-                "spl_associated_token_account".to_string(),
-            ),
-            (self.mint.pubkey(), "test_mint".to_string()),
-            (self.sender_ata, "sender_ata".to_string()),
-            (self.recipient_ata, "recipient_ata".to_string()),
-            (self.sender.pubkey(), "sender".to_string()),
-            (self.recipient.pubkey(), "recipient".to_string()),
-            (self.mint_authority.pubkey(), "mint_authority".to_string()),
-            (
-                solana_system_interface::program::ID,
-                "system_program".to_string(),
-            ),
-        ])
+    fn address_book(&self) -> AddressBook {
+        let mut book = AddressBook::new()
+            .with(self.mint.pubkey(), "test_mint")
+            .with(self.sender.pubkey(), "sender")
+            .with(self.recipient.pubkey(), "recipient")
+            .with(self.mint_authority.pubkey(), "mint_authority");
+        book.insert_under(&self.sender.pubkey(), "ata", self.sender_ata);
+        book.insert_under(&self.recipient.pubkey(), "ata", self.recipient_ata);
+        book
     }
 }
 
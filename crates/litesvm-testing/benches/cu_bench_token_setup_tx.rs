@@ -1,6 +1,5 @@
-use std::collections::HashMap;
-
 use litesvm_testing::prelude::*;
+use litesvm_testing::seed::{seeded_keypair, test_seed};
 
 use litesvm::LiteSVM;
 use litesvm_testing::cu_bench::{benchmark_transaction, TransactionBenchmark};
@@ -13,6 +12,8 @@ use spl_token::solana_program::program_pack::Pack;
 /// Benchmark for a complete token setup transaction
 /// This represents a realistic workflow: create mint + ATA + mint initial supply
 struct TokenSetupTransactionBenchmark {
+    seed: u64,
+    mints_built: u32,
     mint_authority: Keypair,
     mint: Keypair,
     token_account_owner: Keypair,
@@ -21,11 +22,15 @@ struct TokenSetupTransactionBenchmark {
 
 impl TokenSetupTransactionBenchmark {
     fn new() -> Self {
-        let mint_authority = Keypair::new();
-        let mint = Keypair::new();
-        let token_account_owner = Keypair::new();
+        // Seeded keypairs keep the pubkeys in the JSON output stable between runs
+        let seed = test_seed();
+        let mint_authority = seeded_keypair(seed, "mint_authority");
+        let mint = seeded_keypair(seed, "mint_0");
+        let token_account_owner = seeded_keypair(seed, "token_account_owner");
 
         Self {
+            seed,
+            mints_built: 0,
             mint_authority,
             mint,
             token_account_owner,
@@ -54,7 +59,8 @@ impl TransactionBenchmark for TokenSetupTransactionBenchmark {
 
     fn build_transaction(&mut self, svm: &mut LiteSVM) -> Transaction {
         // Use a fresh mint keypair for each transaction to avoid "account already exists" errors
-        self.mint = Keypair::new();
+        self.mints_built += 1;
+        self.mint = seeded_keypair(self.seed, &format!("mint_{}", self.mints_built));
 
         // Get fresh blockhash from the provided SVM
         svm.expire_blockhash();
@@ -123,19 +129,10 @@ impl TransactionBenchmark for TokenSetupTransactionBenchmark {
         transaction
     }
 
-    fn address_book(&self) -> HashMap<Pubkey, String> {
-        HashMap::from_iter(vec![
-            (system_program::ID, "system_program".to_string()),
-            (spl_token::ID, "spl_token".to_string()),
-            (
-                spl_associated_token_account::ID,
-                "spl_associated_token_account".to_string(),
-            ),
-            (
-                solana_compute_budget_interface::ID,
-                "compute_budget".to_string(),
-            ),
-        ])
+    fn address_book(&self) -> AddressBook {
+        AddressBook::new()
+            .with(self.mint_authority.pubkey(), "mint_authority")
+            .with(self.token_account_owner.pubkey(), "token_account_owner")
     }
 }
 
@@ -0,0 +1,178 @@
+//! Write arbitrary accounts straight into LiteSVM.
+//!
+//! Setting up state through transactions means creating, funding and initializing every
+//! account, even when a test only needs it to exist. [`AccountBuilder`] writes the account
+//! directly, with rent-exempt lamports for its data unless told otherwise:
+//!
+//! ```text
+//! AccountBuilder::new()
+//!     .owner(&my_program::ID)
+//!     .borsh(&Config { admin, fee_bps: 30 })
+//!     .write(&mut svm, &config);
+//!
+//! AccountBuilder::new().anchor(&vault).write(&mut svm, &vault_pda);    // `anchor` feature
+//! AccountBuilder::new().lamports(5_000_000_000).write(&mut svm, &alice);
+//! ```
+//!
+//! With the `token` feature, [`AccountBuilder::mint`] and [`AccountBuilder::token_account`]
+//! start from packed SPL Token state. For Token-2022 extensions, see
+//! [`MintBuilder`](crate::token_testing::MintBuilder).
+
+use borsh::BorshSerialize;
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_pubkey::Pubkey;
+use spl_token::solana_program::program_pack::Pack;
+
+/// An account to write into the runtime.
+///
+/// Unless set, the owner is the system program, the data is empty, the account isn't
+/// executable and its lamports are the rent-exempt minimum for its data.
+#[derive(Debug, Clone, Default)]
+pub struct AccountBuilder {
+    lamports: Option<u64>,
+    owner: Pubkey,
+    data: Vec<u8>,
+    executable: bool,
+}
+
+impl AccountBuilder {
+    /// An empty, rent-exempt system account.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the lamports, instead of the rent-exempt minimum.
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = Some(lamports);
+        self
+    }
+
+    /// Sets the owning program.
+    pub fn owner(mut self, owner: &Pubkey) -> Self {
+        self.owner = *owner;
+        self
+    }
+
+    /// Sets the data to `data`, as is.
+    pub fn data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    /// Sets the data to `len` zero bytes, e.g. for an account a program initializes.
+    pub fn space(mut self, len: usize) -> Self {
+        self.data = vec![0; len];
+        self
+    }
+
+    /// Sets the data to `value`'s Borsh serialization.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` fails to serialize.
+    pub fn borsh<T: BorshSerialize>(self, value: &T) -> Self {
+        let data = borsh::to_vec(value).expect("borsh serialization failed");
+        self.data(data)
+    }
+
+    /// Sets the data to `value` packed with [`Pack`], e.g. an SPL Token account.
+    pub fn pack<T: Pack>(self, value: &T) -> Self {
+        let mut data = vec![0; T::LEN];
+        value.pack_into_slice(&mut data);
+        self.data(data)
+    }
+
+    /// Sets the data to an Anchor account's discriminator and Borsh serialization, and the
+    /// owner to its program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `account` fails to serialize.
+    #[cfg(feature = "anchor")]
+    pub fn anchor<T>(self, account: &T) -> Self
+    where
+        T: anchor_lang::AccountSerialize + anchor_lang::Owner,
+    {
+        let mut data = Vec::new();
+        account
+            .try_serialize(&mut data)
+            .expect("anchor account serialization failed");
+        self.owner(&T::owner()).data(data)
+    }
+
+    /// Marks the account executable.
+    ///
+    /// LiteSVM loads executable accounts as programs: the owner must be a BPF loader and the
+    /// data a valid program. [`LiteSVM::add_program`] is usually simpler.
+    pub fn executable(mut self, executable: bool) -> Self {
+        self.executable = executable;
+        self
+    }
+
+    /// An initialized SPL Token mint with `supply` and no freeze authority.
+    #[cfg(feature = "token")]
+    pub fn mint(mint_authority: &Pubkey, supply: u64, decimals: u8) -> Self {
+        use spl_token::{solana_program::program_option::COption, state::Mint};
+
+        let mint = Mint {
+            mint_authority: COption::Some(*mint_authority),
+            supply,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        Self::new().owner(&spl_token::ID).pack(&mint)
+    }
+
+    /// An initialized SPL Token account of `owner`'s, holding `amount` of `mint`.
+    ///
+    /// The mint's supply isn't touched; [`token_testing::mint_to`] keeps the two consistent.
+    ///
+    /// [`token_testing::mint_to`]: crate::token_testing::mint_to
+    #[cfg(feature = "token")]
+    pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Self {
+        use spl_token::{
+            solana_program::program_option::COption,
+            state::{Account as TokenAccount, AccountState},
+        };
+
+        let account = TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        Self::new().owner(&spl_token::ID).pack(&account)
+    }
+
+    /// Builds the account, using `svm`'s rent for the default lamports.
+    pub fn build(self, svm: &LiteSVM) -> Account {
+        let lamports = self
+            .lamports
+            .unwrap_or_else(|| svm.minimum_balance_for_rent_exemption(self.data.len()));
+
+        Account {
+            lamports,
+            data: self.data,
+            owner: self.owner,
+            executable: self.executable,
+            rent_epoch: 0,
+        }
+    }
+
+    /// Builds the account and writes it to `pubkey`, replacing whatever was there.
+    ///
+    /// # Panics
+    ///
+    /// Panics if LiteSVM rejects the account.
+    pub fn write(self, svm: &mut LiteSVM, pubkey: &Pubkey) {
+        let account = self.build(svm);
+        svm.set_account(*pubkey, account)
+            .unwrap_or_else(|e| panic!("writing account {} failed: {}", pubkey, e));
+    }
+}
@@ -0,0 +1,251 @@
+//! Account state assertions for LiteSVM.
+//!
+//! Where the top-level `demand_*` functions inspect a [`TransactionResult`], the assertions here
+//! inspect what a transaction left behind: they read accounts straight out of the [`LiteSVM`]
+//! instance via [`LiteSVM::get_account`].
+//!
+//! ```text
+//! let result = svm.send_transaction(tx);
+//! result.demand_logs_contain("Vault initialized");
+//!
+//! demand_account_exists(&svm, &vault);
+//! demand_owner(&svm, &vault, &my_program::ID);
+//! demand_data_len(&svm, &vault, Vault::LEN);
+//! demand_data_range_eq(&svm, &vault, 0, Vault::DISCRIMINATOR);
+//! demand_account_closed(&svm, &escrow);
+//! ```
+//!
+//! Data mismatches are reported as a hex diff of the differing 16-byte rows:
+//!
+//! ```text
+//! Expected 8 bytes of account 7xKX...Fq9 data at offset 0 but got 8 bytes:
+//!   000000  expected: 01 02 03 04 05 06 07 08
+//!           observed: 01 02 03 ff 05 06 07 08
+//!                              ^^
+//! ```
+//!
+//! [`TransactionResult`]: litesvm::types::TransactionResult
+
+use litesvm::LiteSVM;
+use solana_pubkey::Pubkey;
+
+use crate::{or_panic, DemandError};
+
+/// Asserts that an account exists.
+///
+/// # Panics
+///
+/// Panics if [`LiteSVM::get_account`] returns `None` for `pubkey`.
+pub fn demand_account_exists(svm: &LiteSVM, pubkey: &Pubkey) {
+    or_panic(check_account_exists(svm, pubkey));
+}
+
+/// Checks that an account exists, without panicking.
+///
+/// Non-panicking version of [`demand_account_exists`].
+pub fn check_account_exists(svm: &LiteSVM, pubkey: &Pubkey) -> Result<(), DemandError> {
+    match svm.get_account(pubkey) {
+        Some(_) => Ok(()),
+        None => Err(DemandError::AccountNotFound { pubkey: *pubkey }),
+    }
+}
+
+/// Asserts that an account has been closed.
+///
+/// An account counts as closed when it no longer exists or holds zero lamports, which is how
+/// the runtime treats an account whose lamports were drained at the end of a transaction.
+///
+/// # Panics
+///
+/// Panics if the account still holds lamports.
+pub fn demand_account_closed(svm: &LiteSVM, pubkey: &Pubkey) {
+    or_panic(check_account_closed(svm, pubkey));
+}
+
+/// Checks that an account has been closed, without panicking.
+///
+/// Non-panicking version of [`demand_account_closed`].
+pub fn check_account_closed(svm: &LiteSVM, pubkey: &Pubkey) -> Result<(), DemandError> {
+    match svm.get_account(pubkey) {
+        Some(account) if account.lamports > 0 => Err(DemandError::AccountNotClosed {
+            pubkey: *pubkey,
+            lamports: account.lamports,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Asserts that an account holds exactly `expected` lamports.
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist or its balance differs.
+pub fn demand_lamports(svm: &LiteSVM, pubkey: &Pubkey, expected: u64) {
+    or_panic(check_lamports(svm, pubkey, expected));
+}
+
+/// Checks that an account holds exactly `expected` lamports, without panicking.
+///
+/// Non-panicking version of [`demand_lamports`].
+pub fn check_lamports(svm: &LiteSVM, pubkey: &Pubkey, expected: u64) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    check_field(pubkey, "lamports", expected, account.lamports)
+}
+
+/// Asserts that an account is owned by `expected`.
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist or has a different owner.
+pub fn demand_owner(svm: &LiteSVM, pubkey: &Pubkey, expected: &Pubkey) {
+    or_panic(check_owner(svm, pubkey, expected));
+}
+
+/// Checks that an account is owned by `expected`, without panicking.
+///
+/// Non-panicking version of [`demand_owner`].
+pub fn check_owner(svm: &LiteSVM, pubkey: &Pubkey, expected: &Pubkey) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    check_field(pubkey, "owner", *expected, account.owner)
+}
+
+/// Asserts that an account's data is exactly `expected` bytes long.
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist or its data length differs.
+pub fn demand_data_len(svm: &LiteSVM, pubkey: &Pubkey, expected: usize) {
+    or_panic(check_data_len(svm, pubkey, expected));
+}
+
+/// Checks that an account's data is exactly `expected` bytes long, without panicking.
+///
+/// Non-panicking version of [`demand_data_len`].
+pub fn check_data_len(svm: &LiteSVM, pubkey: &Pubkey, expected: usize) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    check_field(pubkey, "data length", expected, account.data.len())
+}
+
+/// Asserts that an account is (or is not) marked executable.
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist or its executable flag differs.
+pub fn demand_executable(svm: &LiteSVM, pubkey: &Pubkey, expected: bool) {
+    or_panic(check_executable(svm, pubkey, expected));
+}
+
+/// Checks an account's executable flag, without panicking.
+///
+/// Non-panicking version of [`demand_executable`].
+pub fn check_executable(svm: &LiteSVM, pubkey: &Pubkey, expected: bool) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    check_field(pubkey, "executable", expected, account.executable)
+}
+
+/// Asserts that an account's data equals `expected` exactly, including its length.
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist or its data differs. The panic message shows a hex
+/// diff of the differing rows.
+pub fn demand_data_eq(svm: &LiteSVM, pubkey: &Pubkey, expected: &[u8]) {
+    or_panic(check_data_eq(svm, pubkey, expected));
+}
+
+/// Checks that an account's data equals `expected` exactly, without panicking.
+///
+/// Non-panicking version of [`demand_data_eq`].
+pub fn check_data_eq(svm: &LiteSVM, pubkey: &Pubkey, expected: &[u8]) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    if account.data == expected {
+        return Ok(());
+    }
+
+    Err(DemandError::DataMismatch {
+        pubkey: *pubkey,
+        offset: 0,
+        expected: expected.to_vec(),
+        observed: account.data,
+    })
+}
+
+/// Asserts that an account's data contains `expected` starting at byte `offset`.
+///
+/// Useful for checking a single field - a discriminator, an authority, a counter - without
+/// restating the whole account layout.
+///
+/// ```text
+/// // The 32-byte authority field follows an 8-byte discriminator
+/// demand_data_range_eq(&svm, &vault, 8, authority.as_ref());
+/// ```
+///
+/// # Panics
+///
+/// Panics if the account doesn't exist, its data is too short, or the range differs.
+pub fn demand_data_range_eq(svm: &LiteSVM, pubkey: &Pubkey, offset: usize, expected: &[u8]) {
+    or_panic(check_data_range_eq(svm, pubkey, offset, expected));
+}
+
+/// Checks that an account's data contains `expected` at `offset`, without panicking.
+///
+/// Non-panicking version of [`demand_data_range_eq`].
+pub fn check_data_range_eq(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+    offset: usize,
+    expected: &[u8],
+) -> Result<(), DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+
+    let end = offset
+        .saturating_add(expected.len())
+        .min(account.data.len());
+    let observed = account.data.get(offset..end).unwrap_or_default();
+
+    if observed == expected {
+        return Ok(());
+    }
+
+    Err(DemandError::DataMismatch {
+        pubkey: *pubkey,
+        offset,
+        expected: expected.to_vec(),
+        observed: observed.to_vec(),
+    })
+}
+
+fn check_field<T: PartialEq + std::fmt::Display>(
+    pubkey: &Pubkey,
+    field: &'static str,
+    expected: T,
+    observed: T,
+) -> Result<(), DemandError> {
+    if expected == observed {
+        return Ok(());
+    }
+
+    Err(DemandError::AccountMismatch {
+        pubkey: *pubkey,
+        field,
+        expected: expected.to_string(),
+        observed: observed.to_string(),
+    })
+}
@@ -0,0 +1,288 @@
+//! Human-readable names for pubkeys.
+//!
+//! Test output is full of base58 pubkeys. An [`AddressBook`] maps them to labels, and every
+//! piece of output in this crate reads from one: assertion failures, transaction reports,
+//! log snapshots and `cu_bench` context discovery.
+//!
+//! ```text
+//! let mut book = AddressBook::new();               // well-known programs and sysvars
+//! book.insert(alice.pubkey(), "alice");
+//! book.insert_under(&alice.pubkey(), "usdc_ata", alice_usdc);  // alice.usdc_ata
+//! let vault = book.pda(&vault_program::ID, &[b"vault", alice.pubkey().as_ref()]);
+//! assert_eq!(book.name(&vault), "vault_program[vault, alice]");
+//! ```
+//!
+//! [`AddressBook::new`] knows the system program, SPL Token, Token-2022, the associated
+//! token account program, compute budget, the loaders and the sysvars.
+//!
+//! Assertion failures can't take an address book argument, so they use a process-wide one.
+//! [`AddressBook::share`] adds a book's labels to it, and [`address_name`] looks them up:
+//!
+//! ```text
+//! book.share();
+//! demand_account_closed(&svm, &alice_usdc);  // "Expected account alice.usdc_ata to be closed..."
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+use solana_pubkey::Pubkey;
+use solana_sdk_ids::{
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget, loader_v4,
+    native_loader, system_program, sysvar,
+};
+
+use crate::TOKEN_2022_PROGRAM_ID;
+
+/// Labels for pubkeys, used wherever this crate prints one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressBook {
+    labels: HashMap<Pubkey, String>,
+}
+
+impl Default for AddressBook {
+    fn default() -> Self {
+        let well_known = [
+            (system_program::ID, "system_program"),
+            (compute_budget::ID, "compute_budget"),
+            (spl_token::ID, "spl_token"),
+            (TOKEN_2022_PROGRAM_ID, "spl_token_2022"),
+            (
+                spl_associated_token_account::ID,
+                "spl_associated_token_account",
+            ),
+            (bpf_loader::ID, "bpf_loader"),
+            (bpf_loader_deprecated::ID, "bpf_loader_deprecated"),
+            (bpf_loader_upgradeable::ID, "bpf_loader_upgradeable"),
+            (loader_v4::ID, "loader_v4"),
+            (native_loader::ID, "native_loader"),
+            (sysvar::clock::ID, "sysvar.clock"),
+            (sysvar::epoch_rewards::ID, "sysvar.epoch_rewards"),
+            (sysvar::epoch_schedule::ID, "sysvar.epoch_schedule"),
+            (sysvar::fees::ID, "sysvar.fees"),
+            (sysvar::instructions::ID, "sysvar.instructions"),
+            (sysvar::last_restart_slot::ID, "sysvar.last_restart_slot"),
+            (sysvar::recent_blockhashes::ID, "sysvar.recent_blockhashes"),
+            (sysvar::rent::ID, "sysvar.rent"),
+            (sysvar::rewards::ID, "sysvar.rewards"),
+            (sysvar::slot_hashes::ID, "sysvar.slot_hashes"),
+            (sysvar::slot_history::ID, "sysvar.slot_history"),
+            (sysvar::stake_history::ID, "sysvar.stake_history"),
+        ];
+
+        well_known.into_iter().collect()
+    }
+}
+
+impl AddressBook {
+    /// Creates an address book with the well-known programs and sysvars.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an address book without any labels.
+    pub fn empty() -> Self {
+        AddressBook {
+            labels: HashMap::new(),
+        }
+    }
+
+    /// Labels `pubkey`, replacing any previous label.
+    pub fn insert(&mut self, pubkey: Pubkey, label: impl Into<String>) {
+        self.labels.insert(pubkey, label.into());
+    }
+
+    /// Labels `pubkey`, builder style.
+    pub fn with(mut self, pubkey: Pubkey, label: impl Into<String>) -> Self {
+        self.insert(pubkey, label);
+        self
+    }
+
+    /// Labels `pubkey` as `name` under `parent`'s label, e.g. `alice.usdc_ata`.
+    ///
+    /// An unlabeled parent contributes its base58 pubkey. Returns the new label.
+    pub fn insert_under(&mut self, parent: &Pubkey, name: &str, pubkey: Pubkey) -> String {
+        let label = format!("{}.{}", self.name(parent), name);
+        self.insert(pubkey, label.clone());
+        label
+    }
+
+    /// Derives a program address and labels it after the program and its seeds.
+    ///
+    /// Seeds that are labeled pubkeys render as their labels, printable ASCII seeds as
+    /// text and anything else as hex, e.g. `vault_program[vault, alice]`.
+    pub fn pda(&mut self, program_id: &Pubkey, seeds: &[&[u8]]) -> Pubkey {
+        let (pda, _bump) = Pubkey::find_program_address(seeds, program_id);
+        let seeds: Vec<String> = seeds.iter().map(|seed| self.seed_label(seed)).collect();
+        let label = format!("{}[{}]", self.name(program_id), seeds.join(", "));
+        self.insert(pda, label);
+        pda
+    }
+
+    fn seed_label(&self, seed: &[u8]) -> String {
+        if let Ok(pubkey) = Pubkey::try_from(seed) {
+            if let Some(label) = self.label(&pubkey) {
+                return label.to_string();
+            }
+        }
+        let printable = |byte: &u8| byte.is_ascii_graphic() || *byte == b' ';
+        if !seed.is_empty() && seed.iter().all(printable) {
+            return String::from_utf8_lossy(seed).into_owned();
+        }
+        crate::hex(seed)
+    }
+
+    /// Returns `pubkey`'s label, if it has one.
+    pub fn label(&self, pubkey: &Pubkey) -> Option<&str> {
+        self.labels.get(pubkey).map(String::as_str)
+    }
+
+    /// Returns `pubkey`'s label, or its base58 form if it has none.
+    pub fn name(&self, pubkey: &Pubkey) -> String {
+        match self.label(pubkey) {
+            Some(label) => label.to_string(),
+            None => pubkey.to_string(),
+        }
+    }
+
+    /// Returns the pubkey labeled `label`, if any.
+    pub fn pubkey(&self, label: &str) -> Option<Pubkey> {
+        self.labels
+            .iter()
+            .find(|(_, candidate)| candidate.as_str() == label)
+            .map(|(pubkey, _)| *pubkey)
+    }
+
+    /// Iterates over the labeled pubkeys, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, &str)> {
+        self.labels
+            .iter()
+            .map(|(pubkey, label)| (pubkey, label.as_str()))
+    }
+
+    /// The number of labeled pubkeys.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Whether no pubkeys are labeled.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Adds every label in `other`, replacing labels for the same pubkeys.
+    pub fn merge(&mut self, other: &AddressBook) {
+        self.extend(
+            other
+                .iter()
+                .map(|(pubkey, label)| (*pubkey, label.to_string())),
+        );
+    }
+
+    /// Returns a copy of the process-wide address book used by assertion failures.
+    pub fn shared() -> AddressBook {
+        shared()
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Adds this book's labels to the process-wide address book used by assertion failures.
+    ///
+    /// The shared book is used by every test in the process, so labels added here show up
+    /// in other tests' failures too.
+    pub fn share(&self) {
+        shared()
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .merge(self);
+    }
+}
+
+impl<S: Into<String>> FromIterator<(Pubkey, S)> for AddressBook {
+    fn from_iter<I: IntoIterator<Item = (Pubkey, S)>>(iter: I) -> Self {
+        let mut book = AddressBook::empty();
+        book.extend(iter);
+        book
+    }
+}
+
+impl<S: Into<String>> Extend<(Pubkey, S)> for AddressBook {
+    fn extend<I: IntoIterator<Item = (Pubkey, S)>>(&mut self, iter: I) {
+        for (pubkey, label) in iter {
+            self.insert(pubkey, label);
+        }
+    }
+}
+
+impl From<HashMap<Pubkey, String>> for AddressBook {
+    fn from(labels: HashMap<Pubkey, String>) -> Self {
+        AddressBook { labels }
+    }
+}
+
+fn shared() -> &'static RwLock<AddressBook> {
+    static SHARED: OnceLock<RwLock<AddressBook>> = OnceLock::new();
+    SHARED.get_or_init(|| RwLock::new(AddressBook::new()))
+}
+
+/// Names `pubkey` from the process-wide address book, or returns its base58 form.
+///
+/// See [`AddressBook::share`].
+pub fn address_name(pubkey: &Pubkey) -> String {
+    shared()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .name(pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_labels() {
+        let book = AddressBook::new();
+        assert_eq!(book.name(&spl_token::ID), "spl_token");
+        assert_eq!(book.name(&TOKEN_2022_PROGRAM_ID), "spl_token_2022");
+        assert_eq!(book.name(&sysvar::rent::ID), "sysvar.rent");
+        assert_eq!(book.pubkey("loader_v4"), Some(loader_v4::ID));
+        assert!(AddressBook::empty().is_empty());
+    }
+
+    #[test]
+    fn test_hierarchical_labels() {
+        let alice = Pubkey::new_unique();
+        let usdc_ata = Pubkey::new_unique();
+        let mut book = AddressBook::empty().with(alice, "alice");
+
+        assert_eq!(
+            book.insert_under(&alice, "usdc_ata", usdc_ata),
+            "alice.usdc_ata"
+        );
+        assert_eq!(book.pubkey("alice.usdc_ata"), Some(usdc_ata));
+
+        let orphan = Pubkey::new_unique();
+        book.insert_under(&orphan, "ata", Pubkey::new_unique());
+        assert!(book.pubkey(&format!("{}.ata", orphan)).is_some());
+    }
+
+    #[test]
+    fn test_pda_labels() {
+        let program_id = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let mut book = AddressBook::new()
+            .with(program_id, "vault_program")
+            .with(alice, "alice");
+
+        let vault = book.pda(&program_id, &[b"vault", alice.as_ref(), &[7, 0]]);
+
+        assert_eq!(
+            vault,
+            Pubkey::find_program_address(&[b"vault", alice.as_ref(), &[7, 0]], &program_id).0
+        );
+        assert_eq!(book.name(&vault), "vault_program[vault, alice, 0700]");
+    }
+}
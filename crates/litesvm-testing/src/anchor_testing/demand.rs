@@ -0,0 +1,389 @@
+use std::fmt;
+
+use anchor_lang::error::Error as AnchorLangError;
+use litesvm::types::TransactionResult;
+use solana_instruction::error::InstructionError;
+use solana_transaction_error::TransactionError;
+
+use crate::{logs_of, or_panic_with, DemandError};
+
+/// Where Anchor says an error came from, as reported in its `AnchorError` log line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnchorErrorOrigin {
+    /// `AnchorError thrown in {file}:{line}` - raised with `err!` / `error!` / `require!`.
+    Source { file: String, line: u32 },
+    /// `AnchorError caused by account: {name}` - raised by an account constraint.
+    Account(String),
+}
+
+/// An `AnchorError` log line decoded into its parts.
+///
+/// Anchor logs one of these lines whenever an instruction returns an `anchor_lang::error::Error`:
+///
+/// ```text
+/// Program log: AnchorError occurred. Error Code: AlwaysFails. Error Number: 6000. Error Message: This instruction always fails.
+/// Program log: AnchorError thrown in programs/demo/src/lib.rs:12. Error Code: ... Error Number: ... Error Message: ...
+/// Program log: AnchorError caused by account: vault. Error Code: ConstraintMut. Error Number: 2000. Error Message: ...
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorErrorLog {
+    pub error_code: String,
+    pub error_number: u32,
+    pub error_message: String,
+    pub origin: Option<AnchorErrorOrigin>,
+}
+
+impl AnchorErrorLog {
+    /// Parses a single log entry, returning `None` if it isn't an `AnchorError` line.
+    pub fn parse(log: &str) -> Option<Self> {
+        let line = log.strip_prefix("Program log: ").unwrap_or(log);
+        let rest = line.strip_prefix("AnchorError ")?;
+
+        let (origin, rest) = if let Some(rest) = rest.strip_prefix("occurred. ") {
+            (None, rest)
+        } else if let Some(rest) = rest.strip_prefix("thrown in ") {
+            let (location, rest) = rest.split_once(". Error Code: ")?;
+            let (file, line) = location.rsplit_once(':')?;
+            let origin = AnchorErrorOrigin::Source {
+                file: file.to_string(),
+                line: line.parse().ok()?,
+            };
+            (Some(origin), rest)
+        } else if let Some(rest) = rest.strip_prefix("caused by account: ") {
+            let (account, rest) = rest.split_once(". Error Code: ")?;
+            (Some(AnchorErrorOrigin::Account(account.to_string())), rest)
+        } else {
+            return None;
+        };
+
+        let rest = rest.strip_prefix("Error Code: ").unwrap_or(rest);
+        let (error_code, rest) = rest.split_once(". Error Number: ")?;
+        let (error_number, error_message) = rest.split_once(". Error Message: ")?;
+
+        Some(Self {
+            error_code: error_code.to_string(),
+            error_number: error_number.parse().ok()?,
+            error_message: error_message
+                .strip_suffix('.')
+                .unwrap_or(error_message)
+                .to_string(),
+            origin,
+        })
+    }
+}
+
+impl fmt::Display for AnchorErrorLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.error_code, self.error_number)?;
+        match &self.origin {
+            Some(AnchorErrorOrigin::Source { file, line }) => {
+                write!(f, " thrown in {}:{}", file, line)?
+            }
+            Some(AnchorErrorOrigin::Account(account)) => {
+                write!(f, " caused by account {}", account)?
+            }
+            None => {}
+        }
+        write!(f, ": {}", self.error_message)
+    }
+}
+
+/// Returns the first `AnchorError` log line of a transaction, decoded.
+pub fn anchor_error_log(result: &TransactionResult) -> Option<AnchorErrorLog> {
+    logs_of(result)
+        .iter()
+        .find_map(|log| AnchorErrorLog::parse(log))
+}
+
+/// Asserts that a transaction fails with a specific Anchor error, regardless of index.
+///
+/// Accepts either a variant of a program's own `#[error_code]` enum or an
+/// [`anchor_lang::error::ErrorCode`] framework error. Both halves of an Anchor failure must
+/// agree with the expectation:
+///
+/// - the transaction error is `InstructionError::Custom(n)` with the expected error number
+/// - the logs contain `AnchorError ... Error Code: {name}. Error Number: {n}.`
+///
+/// # Arguments
+///
+/// * `expected_error` - The expected `#[error_code]` variant or `anchor_lang::error::ErrorCode`
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the transaction succeeds, fails with a different error, or the Anchor error log
+/// line is missing or names a different error. The panic message includes the observed
+/// error's file/line or account name when Anchor logged one.
+///
+/// # Example
+///
+/// ```text
+/// demand_anchor_error(MyError::Unauthorized, result);
+/// demand_anchor_error(anchor_lang::error::ErrorCode::ConstraintMut, result);
+/// ```
+pub fn demand_anchor_error<E: Into<AnchorLangError>>(expected_error: E, result: TransactionResult) {
+    or_panic_with(check_anchor_error(expected_error, &result), &result);
+}
+
+/// Checks that a transaction fails with a specific Anchor error, without panicking.
+///
+/// Non-panicking version of [`demand_anchor_error`].
+pub fn check_anchor_error<E: Into<AnchorLangError>>(
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_anchor_error_inner(None, expected_error.into(), result)
+}
+
+/// Asserts that a specific instruction fails with a specific Anchor error.
+///
+/// The "surgical" version of [`demand_anchor_error`] for multi-instruction transactions.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction that should fail (0-based)
+/// * `expected_error` - The expected `#[error_code]` variant or `anchor_lang::error::ErrorCode`
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Example
+///
+/// ```text
+/// demand_anchor_error_at_index(1, MyError::Unauthorized, result);
+/// ```
+pub fn demand_anchor_error_at_index<E: Into<AnchorLangError>>(
+    expected_index: u8,
+    expected_error: E,
+    result: TransactionResult,
+) {
+    or_panic_with(
+        check_anchor_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that a specific instruction fails with a specific Anchor error, without panicking.
+///
+/// Non-panicking version of [`demand_anchor_error_at_index`].
+pub fn check_anchor_error_at_index<E: Into<AnchorLangError>>(
+    expected_index: u8,
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_anchor_error_inner(Some(expected_index), expected_error.into(), result)
+}
+
+fn check_anchor_error_inner(
+    expected_index: Option<u8>,
+    expected_error: AnchorLangError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let (expected_name, expected_number) = expected_name_and_number(&expected_error);
+    let expected = format!("AnchorError {} ({})", expected_name, expected_number);
+
+    let Err(e) = result else {
+        return Err(DemandError::UnexpectedSuccess {
+            expected,
+            expected_index,
+        });
+    };
+
+    let TransactionError::InstructionError(observed_index, observed_error) = &e.err else {
+        return Err(DemandError::WrongError {
+            expected,
+            observed: e.err.to_string(),
+            index: None,
+        });
+    };
+
+    if let Some(expected_index) = expected_index {
+        if *observed_index != expected_index {
+            return Err(DemandError::WrongIndex {
+                expected,
+                expected_index,
+                observed_index: *observed_index,
+            });
+        }
+    }
+
+    let index = expected_index.map(|_| *observed_index);
+    let observed_log = anchor_error_log(result);
+
+    let InstructionError::Custom(observed_number) = observed_error else {
+        return Err(DemandError::WrongError {
+            expected,
+            observed: observed_error.to_string(),
+            index,
+        });
+    };
+
+    let Some(observed_log) = observed_log else {
+        return Err(DemandError::WrongError {
+            expected,
+            observed: format!(
+                "custom program error {} without an AnchorError log",
+                observed_number
+            ),
+            index,
+        });
+    };
+
+    if *observed_number != expected_number
+        || observed_log.error_number != expected_number
+        || observed_log.error_code != expected_name
+    {
+        return Err(DemandError::WrongError {
+            expected,
+            observed: format!("AnchorError {}", observed_log),
+            index,
+        });
+    }
+
+    Ok(())
+}
+
+fn expected_name_and_number(error: &AnchorLangError) -> (String, u32) {
+    match error {
+        AnchorLangError::AnchorError(anchor_error) => (
+            anchor_error.error_name.clone(),
+            anchor_error.error_code_number,
+        ),
+        AnchorLangError::ProgramError(program_error) => (
+            format!("{:?}", program_error.program_error),
+            u64::from(program_error.program_error.clone()) as u32,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use litesvm::types::{FailedTransactionMetadata, TransactionMetadata};
+
+    use super::*;
+
+    fn failed(index: u8, code: u32, logs: &[&str]) -> FailedTransactionMetadata {
+        FailedTransactionMetadata {
+            err: TransactionError::InstructionError(index, InstructionError::Custom(code)),
+            meta: TransactionMetadata {
+                logs: logs.iter().map(|l| l.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_occurred() {
+        let log = AnchorErrorLog::parse(
+            "Program log: AnchorError occurred. Error Code: AlwaysFails. Error Number: 6000. Error Message: This instruction always fails.",
+        )
+        .unwrap();
+
+        assert_eq!(log.error_code, "AlwaysFails");
+        assert_eq!(log.error_number, 6000);
+        assert_eq!(log.error_message, "This instruction always fails");
+        assert_eq!(log.origin, None);
+    }
+
+    #[test]
+    fn test_parse_thrown_in_source() {
+        let log = AnchorErrorLog::parse(
+            "Program log: AnchorError thrown in programs/demo/src/lib.rs:42. Error Code: Unauthorized. Error Number: 6001. Error Message: Not allowed.",
+        )
+        .unwrap();
+
+        assert_eq!(
+            log.origin,
+            Some(AnchorErrorOrigin::Source {
+                file: "programs/demo/src/lib.rs".to_string(),
+                line: 42
+            })
+        );
+        assert_eq!(log.error_code, "Unauthorized");
+        assert_eq!(log.error_number, 6001);
+    }
+
+    #[test]
+    fn test_parse_caused_by_account() {
+        let log = AnchorErrorLog::parse(
+            "Program log: AnchorError caused by account: vault. Error Code: ConstraintMut. Error Number: 2000. Error Message: A mut constraint was violated.",
+        )
+        .unwrap();
+
+        assert_eq!(
+            log.origin,
+            Some(AnchorErrorOrigin::Account("vault".to_string()))
+        );
+        assert_eq!(log.error_code, "ConstraintMut");
+        assert_eq!(log.error_number, 2000);
+    }
+
+    #[test]
+    fn test_parse_ignores_other_logs() {
+        assert_eq!(
+            AnchorErrorLog::parse("Program log: Hello from anchor!"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_framework_error_code() {
+        let result: TransactionResult = Err(failed(
+            1,
+            2000,
+            &["Program log: AnchorError caused by account: vault. Error Code: ConstraintMut. Error Number: 2000. Error Message: A mut constraint was violated."],
+        ));
+
+        assert_eq!(
+            check_anchor_error(ErrorCode::ConstraintMut, &result),
+            Ok(())
+        );
+        assert_eq!(
+            check_anchor_error_at_index(1, ErrorCode::ConstraintMut, &result),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_reports_account_origin() {
+        let result: TransactionResult = Err(failed(
+            0,
+            2000,
+            &["Program log: AnchorError caused by account: vault. Error Code: ConstraintMut. Error Number: 2000. Error Message: A mut constraint was violated."],
+        ));
+
+        let err = check_anchor_error(ErrorCode::ConstraintSigner, &result).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected AnchorError ConstraintSigner (2002) but got AnchorError ConstraintMut (2000) caused by account vault: A mut constraint was violated"
+        );
+    }
+
+    #[test]
+    fn test_check_requires_log_line() {
+        let result: TransactionResult = Err(failed(0, 2000, &["Program log: something else"]));
+
+        assert!(matches!(
+            check_anchor_error(ErrorCode::ConstraintMut, &result),
+            Err(DemandError::WrongError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_wrong_index() {
+        let result: TransactionResult = Err(failed(
+            0,
+            2000,
+            &["Program log: AnchorError occurred. Error Code: ConstraintMut. Error Number: 2000. Error Message: A mut constraint was violated."],
+        ));
+
+        assert_eq!(
+            check_anchor_error_at_index(1, ErrorCode::ConstraintMut, &result),
+            Err(DemandError::WrongIndex {
+                expected: "AnchorError ConstraintMut (2000)".to_string(),
+                expected_index: 1,
+                observed_index: 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use anchor_lang::{event::EVENT_IX_TAG_LE, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use litesvm::types::TransactionResult;
+
+use crate::{logs_of, or_panic_with, short_type_name, DemandError};
+
+/// Extracts every event of type `E` emitted by a transaction.
+///
+/// Both ways Anchor can emit an event are decoded:
+///
+/// - **`emit!`**: `Program data: <base64>` log lines
+/// - **`emit_cpi!`**: self-CPI inner instructions whose data starts with Anchor's event
+///   instruction tag
+///
+/// A payload is decoded as `E` when it starts with `E::DISCRIMINATOR`; payloads of other
+/// event types are skipped. Events from logs are returned first, in log order, followed by
+/// events from inner instructions in execution order.
+///
+/// Works with both successful and failed transactions.
+///
+/// # Example
+///
+/// ```text
+/// let transfers = events::<TransferEvent>(&result);
+/// assert_eq!(transfers.len(), 2);
+/// assert_eq!(transfers[0].amount, 100);
+/// ```
+pub fn events<E: Event>(result: &TransactionResult) -> Vec<E> {
+    let meta = match result {
+        Ok(meta) => meta,
+        Err(e) => &e.meta,
+    };
+
+    let from_logs = logs_of(result)
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .flat_map(|data| data.split_whitespace())
+        .filter_map(|chunk| STANDARD.decode(chunk).ok());
+
+    let from_cpi = meta
+        .inner_instructions
+        .iter()
+        .flatten()
+        .filter_map(|inner| inner.instruction.data.strip_prefix(EVENT_IX_TAG_LE))
+        .map(|data| data.to_vec());
+
+    from_logs
+        .chain(from_cpi)
+        .filter_map(|payload| decode_event::<E>(&payload))
+        .collect()
+}
+
+fn decode_event<E: Event>(payload: &[u8]) -> Option<E> {
+    let mut data = payload.strip_prefix(E::DISCRIMINATOR)?;
+    E::deserialize(&mut data).ok()
+}
+
+/// Asserts that a transaction emitted at least one event of type `E` matching `predicate`.
+///
+/// Use `|_| true` to only require that some `E` was emitted.
+///
+/// # Arguments
+///
+/// * `predicate` - Condition the decoded event must satisfy
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if no event of type `E` was emitted, or if none of the emitted ones match. The panic
+/// message reports how many `E` events were decoded.
+///
+/// # Example
+///
+/// ```text
+/// demand_event_emitted::<TransferEvent>(|e| e.amount == 100, result);
+/// ```
+pub fn demand_event_emitted<E: Event>(predicate: impl Fn(&E) -> bool, result: TransactionResult) {
+    or_panic_with(check_event_emitted(predicate, &result), &result);
+}
+
+/// Checks that a transaction emitted a matching event of type `E`, without panicking.
+///
+/// Non-panicking version of [`demand_event_emitted`].
+pub fn check_event_emitted<E: Event>(
+    predicate: impl Fn(&E) -> bool,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let emitted = events::<E>(result);
+
+    if emitted.iter().any(predicate) {
+        return Ok(());
+    }
+
+    Err(DemandError::EventNotFound {
+        event: short_type_name::<E>(),
+        emitted: emitted.len(),
+    })
+}
+
+/// Asserts that a transaction emitted no event of type `E`.
+///
+/// # Example
+///
+/// ```text
+/// demand_no_event::<RefundEvent>(result);
+/// ```
+pub fn demand_no_event<E: Event>(result: TransactionResult) {
+    or_panic_with(check_no_event::<E>(&result), &result);
+}
+
+/// Checks that a transaction emitted no event of type `E`, without panicking.
+///
+/// Non-panicking version of [`demand_no_event`].
+pub fn check_no_event<E: Event>(result: &TransactionResult) -> Result<(), DemandError> {
+    let emitted = events::<E>(result).len();
+
+    if emitted == 0 {
+        return Ok(());
+    }
+
+    Err(DemandError::UnexpectedEvent {
+        event: short_type_name::<E>(),
+        emitted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::*;
+    use litesvm::types::TransactionMetadata;
+    use solana_message::{
+        compiled_instruction::CompiledInstruction, inner_instruction::InnerInstruction,
+    };
+
+    use super::*;
+
+    #[event]
+    pub struct Deposited {
+        pub amount: u64,
+    }
+
+    #[event]
+    pub struct Withdrawn {
+        pub amount: u64,
+    }
+
+    fn program_data<E: Event>(event: &E) -> String {
+        format!("Program data: {}", STANDARD.encode(event.data()))
+    }
+
+    fn self_cpi<E: Event>(event: &E) -> InnerInstruction {
+        InnerInstruction {
+            instruction: CompiledInstruction {
+                program_id_index: 1,
+                accounts: vec![2],
+                data: [EVENT_IX_TAG_LE, &event.data()].concat(),
+            },
+            stack_height: 2,
+        }
+    }
+
+    fn succeeded(logs: Vec<String>, inner: Vec<InnerInstruction>) -> TransactionMetadata {
+        TransactionMetadata {
+            logs,
+            inner_instructions: vec![inner],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_events_from_logs_and_cpi() {
+        let result: TransactionResult = Ok(succeeded(
+            vec![
+                "Program log: Instruction: Deposit".to_string(),
+                program_data(&Deposited { amount: 1 }),
+                program_data(&Withdrawn { amount: 2 }),
+            ],
+            vec![self_cpi(&Deposited { amount: 3 })],
+        ));
+
+        let deposits = events::<Deposited>(&result);
+        assert_eq!(
+            deposits.iter().map(|e| e.amount).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        let withdrawals = events::<Withdrawn>(&result);
+        assert_eq!(withdrawals.len(), 1);
+        assert_eq!(withdrawals[0].amount, 2);
+    }
+
+    #[test]
+    fn test_check_event_emitted() {
+        let result: TransactionResult = Ok(succeeded(
+            vec![program_data(&Deposited { amount: 7 })],
+            vec![],
+        ));
+
+        assert_eq!(
+            check_event_emitted::<Deposited>(|e| e.amount == 7, &result),
+            Ok(())
+        );
+        assert_eq!(
+            check_event_emitted::<Deposited>(|e| e.amount == 8, &result),
+            Err(DemandError::EventNotFound {
+                event: "Deposited".to_string(),
+                emitted: 1,
+            })
+        );
+        assert_eq!(
+            check_event_emitted::<Withdrawn>(|_| true, &result),
+            Err(DemandError::EventNotFound {
+                event: "Withdrawn".to_string(),
+                emitted: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_no_event() {
+        let result: TransactionResult =
+            Ok(succeeded(vec![], vec![self_cpi(&Withdrawn { amount: 1 })]));
+
+        assert_eq!(check_no_event::<Deposited>(&result), Ok(()));
+        assert_eq!(
+            check_no_event::<Withdrawn>(&result),
+            Err(DemandError::UnexpectedEvent {
+                event: "Withdrawn".to_string(),
+                emitted: 1,
+            })
+        );
+    }
+}
@@ -1,4 +1,14 @@
-// mod demand;
 mod build;
+mod demand;
+mod events;
+mod views;
 
 pub use build::build_anchor_program;
+pub use demand::{
+    anchor_error_log, check_anchor_error, check_anchor_error_at_index, demand_anchor_error,
+    demand_anchor_error_at_index, AnchorErrorLog, AnchorErrorOrigin,
+};
+pub use events::{
+    check_event_emitted, check_no_event, demand_event_emitted, demand_no_event, events,
+};
+pub use views::{return_value, simulate_view};
@@ -0,0 +1,81 @@
+use anchor_lang::AnchorDeserialize;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_transaction::Transaction;
+
+use crate::return_data_testing::return_data;
+
+/// Decodes the value returned by an Anchor instruction into its declared Rust type.
+///
+/// Anchor serializes the `T` of a handler returning `Result<T>` with Borsh and passes it to
+/// `set_return_data`. This decodes it back from the transaction's return data.
+///
+/// Returns `None` if no return data was set, or it doesn't decode as exactly one `T`. Since
+/// any later program invocation clears the return data, call the view as the transaction's
+/// last instruction.
+///
+/// # Example
+///
+/// ```text
+/// // pub fn get_price(ctx: Context<GetPrice>) -> Result<Price>
+/// let price = return_value::<Price>(&result).unwrap();
+/// assert_eq!(price.amount, 1_000);
+/// ```
+pub fn return_value<T: AnchorDeserialize>(result: &TransactionResult) -> Option<T> {
+    let (_, data) = return_data(result)?;
+    T::try_from_slice(data).ok()
+}
+
+/// Simulates a transaction calling an Anchor view instruction and decodes its return value.
+///
+/// The simulation doesn't change the SVM's state, so views can be called freely between
+/// other transactions.
+///
+/// Returns `None` if the simulation failed, or its return value doesn't decode as `T`; see
+/// [`return_value`].
+///
+/// # Example
+///
+/// ```text
+/// let tx = Transaction::new_signed_with_payer(&[get_price_ix], Some(&payer.pubkey()), &[&payer], svm.latest_blockhash());
+/// let price = simulate_view::<Price>(&svm, tx).unwrap();
+/// ```
+pub fn simulate_view<T: AnchorDeserialize>(svm: &LiteSVM, tx: Transaction) -> Option<T> {
+    let result = svm.simulate_transaction(tx).map(|simulated| simulated.meta);
+    return_value(&result)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::*;
+    use litesvm::types::TransactionMetadata;
+
+    use super::*;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+    pub struct Price {
+        pub mint: Pubkey,
+        pub amount: u64,
+    }
+
+    fn returned(data: Vec<u8>) -> TransactionMetadata {
+        let mut meta = TransactionMetadata::default();
+        meta.return_data.program_id = Pubkey::new_unique();
+        meta.return_data.data = data;
+        meta
+    }
+
+    #[test]
+    fn test_return_value() {
+        let price = Price {
+            mint: Pubkey::new_unique(),
+            amount: 1_000,
+        };
+
+        let result = Ok(returned(price.try_to_vec().unwrap()));
+        assert_eq!(return_value::<Price>(&result), Some(price));
+
+        // Trailing bytes mean the handler returned something else
+        assert_eq!(return_value::<u64>(&result), None);
+        assert_eq!(return_value::<u64>(&Ok(returned(Vec::new()))), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Balance change assertions for LiteSVM.
+//!
+//! A [`BalanceTracker`] snapshots the lamports - and, for SPL token accounts, the token
+//! amount - of a set of accounts before a transaction runs. Once it has run, the tracker
+//! compares the signed change of every tracked balance against the expected deltas:
+//!
+//! ```text
+//! let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[alice, bob, alice_ata, bob_ata]);
+//! svm.send_transaction(tx).demand_logs_contain("Transfer");
+//!
+//! tracker.demand_deltas(&svm, &[
+//!     BalanceDelta::Lamports(alice, -1_000_000),
+//!     BalanceDelta::Lamports(bob, 1_000_000),
+//!     BalanceDelta::Tokens(alice_ata, -100),
+//!     BalanceDelta::Tokens(bob_ata, 100),
+//! ]);
+//! ```
+//!
+//! Deltas are exact: a tracked balance with no expectation must not have changed. When the
+//! tracker knows the transaction, the fee payer's lamport delta is compared net of the fee
+//! LiteSVM charges for it, so expectations read the same whoever pays. The fee gets its own
+//! row, comparing the fee the test expects, if it set one, with the charged fee.
+//!
+//! Failures render every tracked balance as a table, marking the rows that differ:
+//!
+//! ```text
+//! Expected balance deltas did not match:
+//!   account                                       asset           expected          actual
+//!   Fee1111111111111111111111111111111111111111   lamports               0               0
+//!   Fee1111111111111111111111111111111111111111   fee                -5000           -5000
+//!   A1ice111111111111111111111111111111111111111  lamports        -1000000        -1000000
+//!   A1iceAta11111111111111111111111111111111111   tokens              -100             -99  <-
+//! ```
+
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_pubkey::Pubkey;
+use solana_sdk_ids::{ed25519_program, secp256k1_program, secp256r1_program};
+use solana_transaction::Transaction;
+use spl_token::{
+    solana_program::program_pack::Pack,
+    state::{Account as TokenAccount, Multisig},
+};
+
+use crate::{or_panic, DemandError, TOKEN_2022_PROGRAM_ID};
+
+/// Lamports charged per transaction signature by LiteSVM's default fee structure.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Precompiles whose signatures are charged like transaction signatures.
+const PRECOMPILES: [Pubkey; 3] = [
+    ed25519_program::ID,
+    secp256k1_program::ID,
+    secp256r1_program::ID,
+];
+
+/// Returns the fee LiteSVM charges for `tx`: one [`LAMPORTS_PER_SIGNATURE`] per required
+/// signature and per signature verified by a precompile instruction.
+///
+/// These are the inputs LiteSVM passes to `solana_fee::calculate_fee`, with every feature
+/// enabled as in [`LiteSVM::new`]. LiteSVM does not charge prioritization fees, so compute
+/// unit prices don't contribute.
+pub fn transaction_fee(tx: &Transaction) -> u64 {
+    let message = &tx.message;
+
+    // A precompile instruction's first data byte is the number of signatures it verifies
+    let precompile_signatures: u64 = message
+        .instructions
+        .iter()
+        .filter(|ix| {
+            message
+                .account_keys
+                .get(usize::from(ix.program_id_index))
+                .is_some_and(|program_id| PRECOMPILES.contains(program_id))
+        })
+        .map(|ix| u64::from(ix.data.first().copied().unwrap_or(0)))
+        .sum();
+
+    (u64::from(message.header.num_required_signatures) + precompile_signatures)
+        * LAMPORTS_PER_SIGNATURE
+}
+
+/// An expected signed change to one tracked balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceDelta {
+    /// Change in an account's lamports. For the fee payer this excludes the fee.
+    Lamports(Pubkey, i128),
+
+    /// Change in an SPL token account's token amount, in base units.
+    Tokens(Pubkey, i128),
+}
+
+/// One row of a balance comparison, as reported by [`DemandError::BalanceMismatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceRow {
+    pub pubkey: Pubkey,
+    /// `"lamports"`, `"tokens"`, or `"fee"`.
+    pub asset: &'static str,
+    pub expected: i128,
+    pub actual: i128,
+}
+
+impl BalanceRow {
+    /// Whether the expected and actual deltas agree.
+    pub fn matches(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Balance {
+    lamports: u64,
+    tokens: Option<u64>,
+}
+
+impl Balance {
+    fn read(svm: &LiteSVM, pubkey: &Pubkey) -> Self {
+        let Some(account) = svm.get_account(pubkey) else {
+            return Balance {
+                lamports: 0,
+                tokens: None,
+            };
+        };
+
+        Balance {
+            lamports: account.lamports,
+            tokens: token_amount(&account),
+        }
+    }
+}
+
+/// The type byte Token-2022 writes after the base state of an account with extensions.
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// The amount held by an SPL Token or Token-2022 account, or `None` for other accounts.
+///
+/// A Token-2022 account starts with the SPL Token layout. Any extensions follow an account
+/// type byte, which tells it apart from a mint or multisig of the same length.
+fn token_amount(account: &Account) -> Option<u64> {
+    let data = &account.data;
+    let base = if account.owner == spl_token::ID {
+        data.as_slice()
+    } else if account.owner == TOKEN_2022_PROGRAM_ID {
+        match data.get(TokenAccount::LEN) {
+            None => data.as_slice(),
+            Some(&ACCOUNT_TYPE_ACCOUNT) if data.len() != Multisig::LEN => {
+                &data[..TokenAccount::LEN]
+            }
+            Some(_) => return None,
+        }
+    } else {
+        return None;
+    };
+
+    TokenAccount::unpack(base).ok().map(|state| state.amount)
+}
+
+/// Snapshot of account balances taken before a transaction, for asserting deltas after it.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct BalanceTracker {
+    before: Vec<(Pubkey, Balance)>,
+    fee: Option<Fee>,
+}
+
+/// The fee of the transaction a tracker was created for.
+#[derive(Debug, Clone, Copy)]
+struct Fee {
+    payer: Pubkey,
+    /// What LiteSVM charges, from [`transaction_fee`].
+    charged: u64,
+    /// What the test expects, [`Fee::charged`] unless set with [`BalanceTracker::with_fee`].
+    expected: u64,
+}
+
+impl BalanceTracker {
+    /// Snapshots the balances of `pubkeys`.
+    ///
+    /// Accounts that don't exist yet are tracked with a zero balance. Token amounts are
+    /// tracked for accounts owned by the SPL Token or Token-2022 program.
+    pub fn snapshot(svm: &LiteSVM, pubkeys: &[Pubkey]) -> Self {
+        let mut before: Vec<(Pubkey, Balance)> = Vec::with_capacity(pubkeys.len());
+
+        for pubkey in pubkeys {
+            if !before.iter().any(|(tracked, _)| tracked == pubkey) {
+                before.push((*pubkey, Balance::read(svm, pubkey)));
+            }
+        }
+
+        BalanceTracker { before, fee: None }
+    }
+
+    /// Snapshots the balances of `pubkeys` and of `tx`'s fee payer, separating out the fee.
+    ///
+    /// The fee payer's [`BalanceDelta::Lamports`] expectation excludes the fee LiteSVM charges,
+    /// [`transaction_fee`]. The fee is reported on its own row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tx` has no accounts, and so no fee payer.
+    pub fn snapshot_for(svm: &LiteSVM, tx: &Transaction, pubkeys: &[Pubkey]) -> Self {
+        let fee_payer = *tx
+            .message
+            .account_keys
+            .first()
+            .expect("transaction has no fee payer");
+        let tracked = [&[fee_payer], pubkeys].concat();
+
+        let fee = transaction_fee(tx);
+        BalanceTracker {
+            fee: Some(Fee {
+                payer: fee_payer,
+                charged: fee,
+                expected: fee,
+            }),
+            ..Self::snapshot(svm, &tracked)
+        }
+    }
+
+    /// Sets the fee the test expects the transaction to cost, to compare with the fee LiteSVM
+    /// charges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tracker wasn't created by [`BalanceTracker::snapshot_for`].
+    pub fn with_fee(mut self, fee: u64) -> Self {
+        let tracked = self
+            .fee
+            .as_mut()
+            .expect("with_fee needs a tracker created by snapshot_for");
+        tracked.expected = fee;
+        self
+    }
+
+    /// The fee LiteSVM charges for the transaction, if the tracker knows it.
+    pub fn fee(&self) -> Option<u64> {
+        self.fee.map(|fee| fee.charged)
+    }
+
+    /// Compares every tracked balance with `expected`, returning one row per balance.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DemandError::UntrackedAccount`] if `expected` names an account that isn't
+    /// tracked.
+    pub fn rows(
+        &self,
+        svm: &LiteSVM,
+        expected: &[BalanceDelta],
+    ) -> Result<Vec<BalanceRow>, DemandError> {
+        for delta in expected {
+            let (BalanceDelta::Lamports(pubkey, _) | BalanceDelta::Tokens(pubkey, _)) = delta;
+            if !self.before.iter().any(|(tracked, _)| tracked == pubkey) {
+                return Err(DemandError::UntrackedAccount { pubkey: *pubkey });
+            }
+        }
+
+        let expected_lamports = |pubkey: &Pubkey| {
+            expected.iter().find_map(|delta| match delta {
+                BalanceDelta::Lamports(p, amount) if p == pubkey => Some(*amount),
+                _ => None,
+            })
+        };
+        let expected_tokens = |pubkey: &Pubkey| {
+            expected.iter().find_map(|delta| match delta {
+                BalanceDelta::Tokens(p, amount) if p == pubkey => Some(*amount),
+                _ => None,
+            })
+        };
+
+        let mut rows = Vec::new();
+        for (pubkey, before) in &self.before {
+            let after = Balance::read(svm, pubkey);
+            let fee = self.fee.filter(|fee| fee.payer == *pubkey);
+            let charged = fee.map_or(0, |fee| i128::from(fee.charged));
+
+            rows.push(BalanceRow {
+                pubkey: *pubkey,
+                asset: "lamports",
+                expected: expected_lamports(pubkey).unwrap_or(0),
+                actual: i128::from(after.lamports) - i128::from(before.lamports) + charged,
+            });
+
+            if let Some(fee) = fee {
+                rows.push(BalanceRow {
+                    pubkey: *pubkey,
+                    asset: "fee",
+                    expected: -i128::from(fee.expected),
+                    actual: -charged,
+                });
+            }
+
+            let tokens = expected_tokens(pubkey);
+            if before.tokens.is_some() || after.tokens.is_some() || tokens.is_some() {
+                rows.push(BalanceRow {
+                    pubkey: *pubkey,
+                    asset: "tokens",
+                    expected: tokens.unwrap_or(0),
+                    actual: i128::from(after.tokens.unwrap_or(0))
+                        - i128::from(before.tokens.unwrap_or(0)),
+                });
+            }
+        }
+
+        Ok(rows)
+    }
+
+    /// Asserts that every tracked balance changed by exactly its expected delta.
+    ///
+    /// Tracked balances without an entry in `expected` must be unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a table of every tracked balance if any delta differs, or if `expected`
+    /// names an account that isn't tracked.
+    pub fn demand_deltas(&self, svm: &LiteSVM, expected: &[BalanceDelta]) {
+        or_panic(self.check_deltas(svm, expected));
+    }
+
+    /// Checks that every tracked balance changed by exactly its expected delta, without
+    /// panicking on a mismatch.
+    ///
+    /// Non-panicking version of [`BalanceTracker::demand_deltas`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DemandError::UntrackedAccount`] if `expected` names an account that isn't tracked
+    /// - [`DemandError::BalanceMismatch`] if any delta differs
+    pub fn check_deltas(
+        &self,
+        svm: &LiteSVM,
+        expected: &[BalanceDelta],
+    ) -> Result<(), DemandError> {
+        let rows = self.rows(svm, expected)?;
+
+        if rows.iter().all(BalanceRow::matches) {
+            return Ok(());
+        }
+
+        Err(DemandError::BalanceMismatch { rows })
+    }
+}
@@ -0,0 +1,504 @@
+//! Cross-program invocation (CPI) assertions for LiteSVM.
+//!
+//! The runtime records every instruction a program invokes via CPI in
+//! [`inner_instructions`], grouped by the top-level instruction that made it. [`cpis`] reads
+//! them back as [`Cpi`]s, and the assertions here check which programs were invoked, how
+//! often, and in what order:
+//!
+//! ```text
+//! demand_cpi_invoked(&spl_token::ID, result);
+//! demand_cpi_count(1, 2, result);
+//! demand_cpi_sequence(&[spl_token::ID, system_program::ID], result);
+//! ```
+//!
+//! To pin down *which* instruction was invoked, match on its data and accounts with a
+//! [`CpiMatcher`]:
+//!
+//! ```text
+//! // The vault really called TransferChecked (discriminator 12), on these accounts
+//! let transfer_checked = CpiMatcher::new(spl_token::ID)
+//!     .data_prefix(&[12])
+//!     .accounts(&tx, &[vault_ata, mint, user_ata, vault]);
+//!
+//! demand_cpi(&transfer_checked, svm.send_transaction(tx.clone()));
+//! ```
+//!
+//! Inner instructions refer to programs and accounts by their index in the transaction's
+//! account keys, which the transaction result doesn't include. Program ids are recovered by
+//! pairing each inner instruction with its `invoke` log line from [`parse_logs`], checking
+//! the line's depth against the instruction's stack height. If the logs were truncated, or
+//! otherwise don't line up, the assertions fail with [`DemandError::CpiLogMismatch`] rather
+//! than guess. Accounts are resolved through the transaction given to
+//! [`CpiMatcher::accounts`].
+//!
+//! [`inner_instructions`]: litesvm::types::TransactionMetadata::inner_instructions
+
+use std::fmt;
+
+use litesvm::types::TransactionResult;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+use crate::{address_book::address_name, hex, log_parser::parse_logs, or_panic_with, DemandError};
+
+/// One instruction invoked via CPI, in execution order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpi {
+    /// Index of the top-level instruction that (directly or indirectly) made the CPI.
+    pub instruction_index: u8,
+
+    /// CPI depth: 2 for a CPI made by a top-level instruction, 3 for one made by that CPI,
+    /// and so on.
+    pub depth: usize,
+
+    /// The invoked program.
+    pub program_id: Pubkey,
+
+    /// The instruction data passed to the program.
+    pub data: Vec<u8>,
+
+    /// The instruction's accounts, as indices into the transaction's account keys.
+    pub accounts: Vec<u8>,
+}
+
+impl Cpi {
+    fn describe(&self, account_keys: Option<&[Pubkey]>) -> String {
+        let accounts: Vec<String> = match account_keys {
+            Some(keys) => self
+                .accounts
+                .iter()
+                .map(|i| match keys.get(*i as usize) {
+                    Some(key) => address_name(key),
+                    None => format!("#{}", i),
+                })
+                .collect(),
+            None => self.accounts.iter().map(|i| format!("#{}", i)).collect(),
+        };
+
+        format!(
+            "{} at depth {} in instruction {}, data {}, accounts [{}]",
+            address_name(&self.program_id),
+            self.depth,
+            self.instruction_index,
+            hex(&self.data),
+            accounts.join(", ")
+        )
+    }
+}
+
+impl fmt::Display for Cpi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(None))
+    }
+}
+
+/// Returns every CPI a transaction made, in execution order, whether it succeeded or failed.
+///
+/// The runtime's inner instructions are the source of truth: there is one [`Cpi`] per inner
+/// instruction, at its stack height. Only the program id comes from the `invoke` log lines.
+///
+/// # Errors
+///
+/// Returns [`DemandError::CpiLogMismatch`] if an instruction's logged CPIs don't line up
+/// with its inner instructions, in number or depth, typically because the logs were
+/// truncated.
+pub fn cpis(result: &TransactionResult) -> Result<Vec<Cpi>, DemandError> {
+    let meta = match result {
+        Ok(meta) => meta,
+        Err(e) => &e.meta,
+    };
+    let invocations = parse_logs(result);
+
+    let mut cpis = Vec::new();
+    for (index, inner_instructions) in meta.inner_instructions.iter().enumerate() {
+        // Both list the instruction's CPIs in execution order; skip the invocation itself
+        let logged: Vec<_> = invocations
+            .get(index)
+            .map(|invocation| invocation.iter().skip(1).collect())
+            .unwrap_or_default();
+
+        let recorded_depths: Vec<usize> = inner_instructions
+            .iter()
+            .map(|inner| usize::from(inner.stack_height))
+            .collect();
+        let logged_depths: Vec<usize> = logged.iter().map(|cpi| cpi.depth).collect();
+        if recorded_depths != logged_depths {
+            return Err(DemandError::CpiLogMismatch {
+                index: index as u8,
+                recorded: recorded_depths,
+                logged: logged_depths,
+            });
+        }
+
+        cpis.extend(
+            logged
+                .iter()
+                .zip(inner_instructions)
+                .map(|(cpi, inner)| Cpi {
+                    instruction_index: index as u8,
+                    depth: usize::from(inner.stack_height),
+                    program_id: cpi.program_id,
+                    data: inner.instruction.data.clone(),
+                    accounts: inner.instruction.accounts.clone(),
+                }),
+        );
+    }
+
+    Ok(cpis)
+}
+
+/// Matches a CPI by program, and optionally by instruction data prefix and accounts.
+///
+/// # Example
+///
+/// ```text
+/// let matcher = CpiMatcher::new(spl_token::ID)
+///     .data_prefix(&[12]) // TransferChecked
+///     .accounts(&tx, &[source, mint, destination, authority]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CpiMatcher {
+    program_id: Pubkey,
+    data_prefix: Vec<u8>,
+    accounts: Option<Vec<Pubkey>>,
+    account_keys: Vec<Pubkey>,
+}
+
+impl CpiMatcher {
+    /// Matches any CPI to `program_id`.
+    pub fn new(program_id: Pubkey) -> Self {
+        CpiMatcher {
+            program_id,
+            data_prefix: Vec::new(),
+            accounts: None,
+            account_keys: Vec::new(),
+        }
+    }
+
+    /// Also requires the instruction data to start with `prefix`, typically a discriminator.
+    pub fn data_prefix(mut self, prefix: &[u8]) -> Self {
+        self.data_prefix = prefix.to_vec();
+        self
+    }
+
+    /// Also requires the instruction's accounts to be exactly `accounts`, in order.
+    ///
+    /// `tx` is the transaction being tested; its account keys resolve the indices recorded
+    /// for each CPI.
+    pub fn accounts(mut self, tx: &Transaction, accounts: &[Pubkey]) -> Self {
+        self.accounts = Some(accounts.to_vec());
+        self.account_keys = tx.message.account_keys.clone();
+        self
+    }
+
+    /// Whether `cpi` satisfies every condition of this matcher.
+    pub fn matches(&self, cpi: &Cpi) -> bool {
+        if cpi.program_id != self.program_id || !cpi.data.starts_with(&self.data_prefix) {
+            return false;
+        }
+
+        let Some(expected) = &self.accounts else {
+            return true;
+        };
+
+        cpi.accounts.len() == expected.len()
+            && cpi
+                .accounts
+                .iter()
+                .zip(expected)
+                .all(|(i, key)| self.account_keys.get(*i as usize) == Some(key))
+    }
+}
+
+impl fmt::Display for CpiMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a CPI to {}", address_name(&self.program_id))?;
+        if !self.data_prefix.is_empty() {
+            write!(f, " with data prefix {}", hex(&self.data_prefix))?;
+        }
+        if let Some(accounts) = &self.accounts {
+            let accounts: Vec<String> = accounts.iter().map(address_name).collect();
+            write!(f, " with accounts [{}]", accounts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Asserts that `program_id` was invoked via CPI at least once, at any depth.
+///
+/// # Arguments
+///
+/// * `program_id` - The program that should have been invoked
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if no CPI invoked `program_id`, listing the CPIs that were made.
+///
+/// # Example
+///
+/// ```text
+/// demand_cpi_invoked(&spl_token::ID, result);
+/// ```
+pub fn demand_cpi_invoked(program_id: &Pubkey, result: TransactionResult) {
+    or_panic_with(check_cpi_invoked(program_id, &result), &result);
+}
+
+/// Checks that `program_id` was invoked via CPI, without panicking.
+///
+/// Non-panicking version of [`demand_cpi_invoked`].
+pub fn check_cpi_invoked(
+    program_id: &Pubkey,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_cpi(&CpiMatcher::new(*program_id), result)
+}
+
+/// Asserts that some CPI satisfies `matcher`.
+///
+/// # Panics
+///
+/// Panics if no CPI matches, listing the CPIs that were made with their data and accounts.
+///
+/// # Example
+///
+/// ```text
+/// let transfer_checked = CpiMatcher::new(spl_token::ID).data_prefix(&[12]);
+/// demand_cpi(&transfer_checked, result);
+/// ```
+pub fn demand_cpi(matcher: &CpiMatcher, result: TransactionResult) {
+    or_panic_with(check_cpi(matcher, &result), &result);
+}
+
+/// Checks that some CPI satisfies `matcher`, without panicking.
+///
+/// Non-panicking version of [`demand_cpi`].
+pub fn check_cpi(matcher: &CpiMatcher, result: &TransactionResult) -> Result<(), DemandError> {
+    let cpis = cpis(result)?;
+
+    if cpis.iter().any(|cpi| matcher.matches(cpi)) {
+        return Ok(());
+    }
+
+    let account_keys = matcher.accounts.as_ref().map(|_| &matcher.account_keys[..]);
+    Err(DemandError::CpiNotFound {
+        expected: matcher.to_string(),
+        cpis: cpis.iter().map(|cpi| cpi.describe(account_keys)).collect(),
+    })
+}
+
+/// Asserts that the top-level instruction at `expected_index` made exactly `count` CPIs.
+///
+/// CPIs at every depth count, including those made by the instruction's CPIs.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the top-level instruction (0-based)
+/// * `count` - The exact number of CPIs expected
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the instruction made a different number of CPIs, listing the ones it made.
+///
+/// # Example
+///
+/// ```text
+/// // A swap transfers in, then out
+/// demand_cpi_count(1, 2, result);
+/// ```
+pub fn demand_cpi_count(expected_index: u8, count: usize, result: TransactionResult) {
+    or_panic_with(check_cpi_count(expected_index, count, &result), &result);
+}
+
+/// Checks that the instruction at `expected_index` made exactly `count` CPIs, without
+/// panicking.
+///
+/// Non-panicking version of [`demand_cpi_count`].
+pub fn check_cpi_count(
+    expected_index: u8,
+    count: usize,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let cpis: Vec<String> = cpis(result)?
+        .iter()
+        .filter(|cpi| cpi.instruction_index == expected_index)
+        .map(Cpi::to_string)
+        .collect();
+
+    if cpis.len() == count {
+        return Ok(());
+    }
+
+    Err(DemandError::WrongCpiCount {
+        index: expected_index,
+        expected_count: count,
+        cpis,
+    })
+}
+
+/// Asserts that the programs invoked via CPI, across the whole transaction and in execution
+/// order, are exactly `expected`.
+///
+/// # Panics
+///
+/// Panics if the sequence differs, showing the expected and observed sequences.
+///
+/// # Example
+///
+/// ```text
+/// demand_cpi_sequence(&[spl_token::ID, system_program::ID, spl_token::ID], result);
+/// ```
+pub fn demand_cpi_sequence(expected: &[Pubkey], result: TransactionResult) {
+    or_panic_with(check_cpi_sequence(expected, &result), &result);
+}
+
+/// Checks that the programs invoked via CPI are exactly `expected`, without panicking.
+///
+/// Non-panicking version of [`demand_cpi_sequence`].
+pub fn check_cpi_sequence(
+    expected: &[Pubkey],
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let observed: Vec<Pubkey> = cpis(result)?.iter().map(|cpi| cpi.program_id).collect();
+
+    if observed == expected {
+        return Ok(());
+    }
+
+    Err(DemandError::WrongCpiSequence {
+        expected: expected.to_vec(),
+        observed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionMetadata;
+    use solana_message::{
+        compiled_instruction::CompiledInstruction, inner_instruction::InnerInstruction,
+    };
+
+    use super::*;
+
+    const PROGRAM: &str = "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const TOKEN: &str = "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const SYSTEM: &str = "Program 11111111111111111111111111111111";
+
+    fn inner(data: &[u8], accounts: &[u8], stack_height: u8) -> InnerInstruction {
+        InnerInstruction {
+            instruction: CompiledInstruction {
+                program_id_index: 0,
+                accounts: accounts.to_vec(),
+                data: data.to_vec(),
+            },
+            stack_height,
+        }
+    }
+
+    fn nested_cpis() -> TransactionMetadata {
+        TransactionMetadata {
+            logs: vec![
+                format!("{} invoke [1]", SYSTEM),
+                format!("{} success", SYSTEM),
+                format!("{} invoke [1]", PROGRAM),
+                format!("{} invoke [2]", TOKEN),
+                format!("{} success", TOKEN),
+                format!("{} invoke [2]", PROGRAM),
+                format!("{} invoke [3]", SYSTEM),
+                format!("{} success", SYSTEM),
+                format!("{} success", PROGRAM),
+                format!("{} success", PROGRAM),
+            ],
+            inner_instructions: vec![
+                vec![],
+                vec![
+                    inner(&[12, 1], &[3, 4, 5], 2),
+                    inner(&[7], &[1], 2),
+                    inner(&[2, 0, 0, 0], &[1, 6], 3),
+                ],
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cpis_pairs_inner_instructions_with_invocations() {
+        let cpis = cpis(&Ok(nested_cpis())).unwrap();
+
+        let programs: Vec<_> = cpis.iter().map(|cpi| cpi.program_id).collect();
+        assert_eq!(
+            programs,
+            vec![
+                spl_token::ID,
+                "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"
+                    .parse()
+                    .unwrap(),
+                solana_system_interface::program::ID,
+            ]
+        );
+        assert!(cpis.iter().all(|cpi| cpi.instruction_index == 1));
+        assert_eq!(cpis[2].depth, 3);
+        assert_eq!(cpis[0].data, vec![12, 1]);
+        assert_eq!(cpis[0].accounts, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_cpis_reports_logs_that_dont_line_up() {
+        let mut truncated = nested_cpis();
+        truncated.logs.truncate(5);
+        truncated.logs.push("Log truncated".to_string());
+
+        assert_eq!(
+            cpis(&Ok(truncated)),
+            Err(DemandError::CpiLogMismatch {
+                index: 1,
+                recorded: vec![2, 2, 3],
+                logged: vec![2],
+            })
+        );
+
+        let mut flattened = nested_cpis();
+        flattened.inner_instructions[1][2].stack_height = 2;
+        assert!(matches!(
+            check_cpi_invoked(&spl_token::ID, &Ok(flattened)),
+            Err(DemandError::CpiLogMismatch { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cpi_assertions() {
+        let result = Ok(nested_cpis());
+
+        assert_eq!(check_cpi_invoked(&spl_token::ID, &result), Ok(()));
+        assert_eq!(check_cpi_count(1, 3, &result), Ok(()));
+        assert_eq!(check_cpi_count(0, 0, &result), Ok(()));
+        assert_eq!(
+            check_cpi(&CpiMatcher::new(spl_token::ID).data_prefix(&[12]), &result),
+            Ok(())
+        );
+
+        assert!(matches!(
+            check_cpi(&CpiMatcher::new(spl_token::ID).data_prefix(&[3]), &result),
+            Err(DemandError::CpiNotFound { cpis, .. }) if cpis.len() == 3
+        ));
+        assert!(matches!(
+            check_cpi_count(1, 2, &result),
+            Err(DemandError::WrongCpiCount { cpis, .. }) if cpis.len() == 3
+        ));
+        assert!(matches!(
+            check_cpi_sequence(&[spl_token::ID], &result),
+            Err(DemandError::WrongCpiSequence { observed, .. }) if observed.len() == 3
+        ));
+    }
+
+    #[test]
+    fn test_hex_elides_long_data() {
+        assert_eq!(hex(&[]), "(empty)");
+        assert_eq!(hex(&[0x0c, 0xff]), "0cff");
+        assert_eq!(
+            hex(&[0xab; 20]),
+            format!("{}... (20 bytes)", "ab".repeat(16))
+        );
+    }
+}
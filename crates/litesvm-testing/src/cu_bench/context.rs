@@ -7,7 +7,7 @@ use solana_message::Message;
 use solana_pubkey::Pubkey;
 use solana_transaction::Transaction;
 
-use crate::cu_bench::InstructionBenchmark;
+use crate::{address_book::AddressBook, cu_bench::InstructionBenchmark};
 
 /// Execution context discovered through simulation (for instructions)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,14 +103,14 @@ pub fn discover_instruction_context<T: InstructionBenchmark>(
 fn extract_program_context(
     transaction: &Transaction,
     simulation: &SimulatedTransactionInfo,
-    address_book: &HashMap<Pubkey, String>,
+    address_book: &AddressBook,
 ) -> ProgramContext {
     let target_instruction = &transaction.message.instructions[0]; // Only instruction
     let program_id = transaction.message.account_keys[target_instruction.program_id_index as usize];
 
     ProgramContext {
         program_id,
-        program_name: lookup_program_name(program_id, address_book),
+        program_name: address_book.name(&program_id),
         cpi_count: simulation.meta.inner_instructions.len(),
     }
 }
@@ -122,19 +122,12 @@ fn extract_execution_stats(simulation: &SimulatedTransactionInfo) -> ExecutionSt
     }
 }
 
-fn lookup_program_name(program_id: Pubkey, address_book: &HashMap<Pubkey, String>) -> String {
-    address_book
-        .get(&program_id)
-        .cloned()
-        .unwrap_or_else(|| program_id.to_string())
-}
-
 /// Discover execution context for a transaction workflow
 pub fn discover_transaction_context(
     transaction: &Transaction,
     workflow_name: String,
     svm: &mut LiteSVM,
-    address_book: &HashMap<Pubkey, String>,
+    address_book: &AddressBook,
 ) -> TransactionExecutionContext {
     // Simulate the transaction to extract context
     let simulation = svm.simulate_transaction(transaction.clone()).unwrap();
@@ -157,7 +150,7 @@ fn extract_workflow_context(
     transaction: &Transaction,
     simulation: &SimulatedTransactionInfo,
     workflow_name: String,
-    address_book: &HashMap<Pubkey, String>,
+    address_book: &AddressBook,
 ) -> WorkflowContext {
     // Extract all unique programs involved
     let mut program_usage: HashMap<Pubkey, usize> = HashMap::new();
@@ -168,7 +161,7 @@ fn extract_workflow_context(
         let program_id = transaction.message.account_keys[instruction.program_id_index as usize];
         *program_usage.entry(program_id).or_insert(0) += 1;
 
-        let program_name = lookup_program_name(program_id, address_book);
+        let program_name = address_book.name(&program_id);
         cpi_sequence.push(program_name);
     }
 
@@ -179,7 +172,7 @@ fn extract_workflow_context(
                 [inner_instruction.instruction.program_id_index as usize];
             *program_usage.entry(program_id).or_insert(0) += 1;
 
-            let program_name = lookup_program_name(program_id, address_book);
+            let program_name = address_book.name(&program_id);
             cpi_sequence.push(format!("{}_cpi", program_name));
         }
     }
@@ -189,7 +182,7 @@ fn extract_workflow_context(
         .into_iter()
         .map(|(program_id, instruction_count)| ProgramInfo {
             program_id,
-            program_name: lookup_program_name(program_id, address_book),
+            program_name: address_book.name(&program_id),
             instruction_count,
         })
         .collect();
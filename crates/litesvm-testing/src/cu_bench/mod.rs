@@ -4,13 +4,13 @@
 //! of Solana instructions, similar to how the Helius Priority Fee API analyzes
 //! transaction fees.
 
-use std::collections::HashMap;
-
 use litesvm::LiteSVM;
 use solana_instruction::Instruction;
 use solana_pubkey::Pubkey;
 use solana_transaction::Transaction;
 
+use crate::address_book::AddressBook;
+
 pub mod context;
 pub mod estimate;
 pub mod runner;
@@ -40,8 +40,10 @@ pub trait InstructionBenchmark {
     fn sign_transaction(&self, unsigned_tx: Transaction) -> Transaction;
 
     /// Provide names for programs/accounts this benchmark interacts with
-    fn address_book(&self) -> HashMap<Pubkey, String> {
-        HashMap::new()
+    ///
+    /// The default names the well-known programs and sysvars.
+    fn address_book(&self) -> AddressBook {
+        AddressBook::new()
     }
 }
 
@@ -57,7 +59,9 @@ pub trait TransactionBenchmark {
     fn build_transaction(&mut self, svm: &mut LiteSVM) -> Transaction;
 
     /// Provide names for programs/accounts this benchmark interacts with
-    fn address_book(&self) -> HashMap<Pubkey, String> {
-        HashMap::new()
+    ///
+    /// The default names the well-known programs and sysvars.
+    fn address_book(&self) -> AddressBook {
+        AddressBook::new()
     }
 }
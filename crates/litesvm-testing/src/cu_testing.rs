@@ -0,0 +1,335 @@
+//! Compute unit assertions for LiteSVM.
+//!
+//! Where the `cu_bench` feature measures compute units statistically, these assertions guard
+//! a single transaction against CU regressions on the spot:
+//!
+//! ```text
+//! demand_cu_at_most(12_000, result);
+//! demand_cu_between(9_000, 12_000, result);
+//! demand_cu_within_pct_of(10_400, 5.0, result);
+//!
+//! // Only the second instruction's budget
+//! demand_cu_at_most_at_index(1, 4_000, result);
+//! ```
+//!
+//! Transaction-wide assertions read [`compute_units_consumed`] and work for both successful
+//! and failed transactions. The per-instruction variant reads the
+//! `Program <id> consumed X of Y compute units` log line of each top-level invocation.
+//!
+//! [`compute_units_consumed`]: litesvm::types::TransactionMetadata::compute_units_consumed
+
+use litesvm::types::TransactionResult;
+
+use crate::{log_parser::parse_logs, or_panic_with, DemandError};
+
+/// Returns the compute units consumed by a transaction, whether it succeeded or failed.
+pub fn compute_units(result: &TransactionResult) -> u64 {
+    match result {
+        Ok(meta) => meta.compute_units_consumed,
+        Err(e) => e.meta.compute_units_consumed,
+    }
+}
+
+/// Returns the compute units consumed by each top-level instruction, in instruction order.
+///
+/// Entries come from the `consumed X of Y compute units` line of each top-level invocation
+/// parsed by [`parse_logs`]. An entry is `None` when the instruction logged no such line, as
+/// builtin programs such as the system program don't. Instructions after a failure never
+/// run, so they have no entry.
+pub fn instruction_compute_units(result: &TransactionResult) -> Vec<Option<u64>> {
+    parse_logs(result)
+        .iter()
+        .map(|invocation| invocation.compute_units_consumed)
+        .collect()
+}
+
+/// Asserts that a transaction consumed at most `limit` compute units.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum compute units, inclusive
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the transaction consumed more than `limit` compute units.
+///
+/// # Example
+///
+/// ```text
+/// demand_cu_at_most(12_000, result);
+/// ```
+pub fn demand_cu_at_most(limit: u64, result: TransactionResult) {
+    or_panic_with(check_cu_at_most(limit, &result), &result);
+}
+
+/// Checks that a transaction consumed at most `limit` compute units, without panicking.
+///
+/// Non-panicking version of [`demand_cu_at_most`].
+pub fn check_cu_at_most(limit: u64, result: &TransactionResult) -> Result<(), DemandError> {
+    check_range(
+        format!("at most {}", limit),
+        0,
+        limit,
+        compute_units(result),
+        None,
+    )
+}
+
+/// Asserts that a transaction consumed between `lo` and `hi` compute units, inclusive.
+///
+/// A lower bound catches the opposite surprise to a regression: a path that silently stopped
+/// doing work.
+///
+/// # Panics
+///
+/// Panics if the consumed compute units fall outside `lo..=hi`.
+///
+/// # Example
+///
+/// ```text
+/// demand_cu_between(9_000, 12_000, result);
+/// ```
+pub fn demand_cu_between(lo: u64, hi: u64, result: TransactionResult) {
+    or_panic_with(check_cu_between(lo, hi, &result), &result);
+}
+
+/// Checks that a transaction consumed between `lo` and `hi` compute units, without panicking.
+///
+/// Non-panicking version of [`demand_cu_between`].
+pub fn check_cu_between(lo: u64, hi: u64, result: &TransactionResult) -> Result<(), DemandError> {
+    check_range(
+        format!("between {} and {}", lo, hi),
+        lo,
+        hi,
+        compute_units(result),
+        None,
+    )
+}
+
+/// Asserts that a transaction consumed within `pct` percent of `baseline` compute units.
+///
+/// # Arguments
+///
+/// * `baseline` - The expected compute units, typically a previously recorded measurement
+/// * `pct` - Allowed deviation either side of `baseline`, in percent
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the consumed compute units deviate from `baseline` by more than `pct` percent.
+///
+/// # Example
+///
+/// ```text
+/// // Accepts 9_880..=10_920
+/// demand_cu_within_pct_of(10_400, 5.0, result);
+/// ```
+pub fn demand_cu_within_pct_of(baseline: u64, pct: f64, result: TransactionResult) {
+    or_panic_with(check_cu_within_pct_of(baseline, pct, &result), &result);
+}
+
+/// Checks that a transaction consumed within `pct` percent of `baseline` compute units,
+/// without panicking.
+///
+/// Non-panicking version of [`demand_cu_within_pct_of`].
+pub fn check_cu_within_pct_of(
+    baseline: u64,
+    pct: f64,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let tolerance = baseline as f64 * pct.abs() / 100.0;
+    let lo = (baseline as f64 - tolerance).max(0.0).ceil() as u64;
+    let hi = (baseline as f64 + tolerance).floor() as u64;
+
+    check_range(
+        format!("within {}% of {} ({}..={})", pct, baseline, lo, hi),
+        lo,
+        hi,
+        compute_units(result),
+        None,
+    )
+}
+
+/// Asserts that the top-level instruction at `expected_index` consumed at most `limit`
+/// compute units.
+///
+/// The figure comes from the instruction's `consumed X of Y compute units` log line, so it
+/// includes any CPIs the instruction made.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction to check (0-based)
+/// * `limit` - Maximum compute units, inclusive
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the instruction consumed more than `limit` compute units, or if it logged no
+/// compute unit line (see [`instruction_compute_units`]).
+///
+/// # Example
+///
+/// ```text
+/// demand_cu_at_most_at_index(1, 4_000, result);
+/// ```
+pub fn demand_cu_at_most_at_index(expected_index: u8, limit: u64, result: TransactionResult) {
+    or_panic_with(
+        check_cu_at_most_at_index(expected_index, limit, &result),
+        &result,
+    );
+}
+
+/// Checks that the instruction at `expected_index` consumed at most `limit` compute units,
+/// without panicking.
+///
+/// Non-panicking version of [`demand_cu_at_most_at_index`].
+pub fn check_cu_at_most_at_index(
+    expected_index: u8,
+    limit: u64,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let units = instruction_compute_units(result);
+
+    let Some(Some(observed)) = units.get(expected_index as usize) else {
+        return Err(DemandError::ComputeUnitsUnavailable {
+            index: expected_index,
+            instructions: units.len(),
+        });
+    };
+
+    check_range(
+        format!("at most {}", limit),
+        0,
+        limit,
+        *observed,
+        Some(expected_index),
+    )
+}
+
+fn check_range(
+    expected: String,
+    lo: u64,
+    hi: u64,
+    observed: u64,
+    index: Option<u8>,
+) -> Result<(), DemandError> {
+    if (lo..=hi).contains(&observed) {
+        return Ok(());
+    }
+
+    Err(DemandError::ComputeUnits {
+        expected,
+        observed,
+        index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::{FailedTransactionMetadata, TransactionMetadata};
+    use solana_instruction::error::InstructionError;
+    use solana_transaction_error::TransactionError;
+
+    use super::*;
+
+    const PROGRAM: &str = "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const TOKEN: &str = "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const SYSTEM: &str = "Program 11111111111111111111111111111111";
+
+    fn succeeded(logs: Vec<String>, compute_units_consumed: u64) -> TransactionMetadata {
+        TransactionMetadata {
+            logs,
+            compute_units_consumed,
+            ..Default::default()
+        }
+    }
+
+    fn failed(logs: Vec<String>, compute_units_consumed: u64) -> FailedTransactionMetadata {
+        FailedTransactionMetadata {
+            err: TransactionError::InstructionError(1, InstructionError::Custom(1)),
+            meta: succeeded(logs, compute_units_consumed),
+        }
+    }
+
+    fn three_instructions() -> Vec<String> {
+        vec![
+            format!("{} invoke [1]", SYSTEM),
+            format!("{} success", SYSTEM),
+            format!("{} invoke [1]", PROGRAM),
+            format!("{} invoke [2]", TOKEN),
+            format!("{} consumed 2000 of 195000 compute units", TOKEN),
+            format!("{} success", TOKEN),
+            format!("{} consumed 5000 of 199850 compute units", PROGRAM),
+            format!("{} success", PROGRAM),
+            format!("{} invoke [1]", PROGRAM),
+            format!("{} consumed 300 of 194850 compute units", PROGRAM),
+            format!("{} failed: custom program error: 0x1", PROGRAM),
+        ]
+    }
+
+    #[test]
+    fn test_instruction_compute_units() {
+        let result: TransactionResult = Ok(succeeded(three_instructions(), 5450));
+
+        assert_eq!(
+            instruction_compute_units(&result),
+            vec![None, Some(5000), Some(300)]
+        );
+    }
+
+    #[test]
+    fn test_transaction_wide_checks() {
+        let result: TransactionResult = Err(failed(three_instructions(), 5450));
+
+        assert_eq!(check_cu_at_most(5450, &result), Ok(()));
+        assert_eq!(check_cu_between(5000, 6000, &result), Ok(()));
+        assert_eq!(check_cu_within_pct_of(5000, 10.0, &result), Ok(()));
+
+        assert_eq!(
+            check_cu_within_pct_of(5000, 5.0, &result),
+            Err(DemandError::ComputeUnits {
+                expected: "within 5% of 5000 (4750..=5250)".to_string(),
+                observed: 5450,
+                index: None,
+            })
+        );
+        assert_eq!(
+            check_cu_between(100, 200, &result),
+            Err(DemandError::ComputeUnits {
+                expected: "between 100 and 200".to_string(),
+                observed: 5450,
+                index: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_cu_at_most_at_index() {
+        let result: TransactionResult = Ok(succeeded(three_instructions(), 5450));
+
+        assert_eq!(check_cu_at_most_at_index(1, 5000, &result), Ok(()));
+        assert_eq!(
+            check_cu_at_most_at_index(1, 4999, &result),
+            Err(DemandError::ComputeUnits {
+                expected: "at most 4999".to_string(),
+                observed: 5000,
+                index: Some(1),
+            })
+        );
+        assert_eq!(
+            check_cu_at_most_at_index(0, 100, &result),
+            Err(DemandError::ComputeUnitsUnavailable {
+                index: 0,
+                instructions: 3,
+            })
+        );
+        assert_eq!(
+            check_cu_at_most_at_index(3, 100, &result),
+            Err(DemandError::ComputeUnitsUnavailable {
+                index: 3,
+                instructions: 3,
+            })
+        );
+    }
+}
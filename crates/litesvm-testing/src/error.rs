@@ -0,0 +1,820 @@
+use std::fmt;
+
+use solana_pubkey::Pubkey;
+
+use crate::{
+    address_book::address_name,
+    balance_testing::BalanceRow,
+    error_registry::{error_name, DecodedError},
+    hex,
+    snapshot_testing::UPDATE_SNAPSHOTS_ENV,
+};
+
+/// Structured failure returned by the `check_*` family of assertions.
+///
+/// Every `demand_*` function is a thin wrapper that panics with this error's
+/// [`Display`](fmt::Display) output. Use the `check_*` functions directly when a failure
+/// needs to be inspected rather than raised - property tests, retry loops, or custom
+/// reporters.
+///
+/// Expected and observed values are carried in their display form so that one error type
+/// can describe transaction, instruction, and program-specific errors alike.
+///
+/// ```text
+/// match check_system_error(SystemError::ResultWithNegativeLamports, &result) {
+///     Ok(()) => {}
+///     Err(DemandError::WrongIndex { observed_index, .. }) => retry_from(observed_index),
+///     Err(other) => panic!("{other}"),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DemandError {
+    /// The transaction succeeded but an error was expected.
+    UnexpectedSuccess {
+        expected: String,
+        expected_index: Option<u8>,
+    },
+
+    /// The transaction failed, but at a different instruction than expected.
+    WrongIndex {
+        expected: String,
+        expected_index: u8,
+        observed_index: u8,
+    },
+
+    /// The transaction failed with a different error than expected.
+    ///
+    /// `index` is the instruction index of the observed error when one is known.
+    WrongError {
+        expected: String,
+        observed: String,
+        index: Option<u8>,
+    },
+
+    /// The transaction failed with a custom error code, but from a different program than
+    /// the one whose error enum was expected, so decoding the code would be meaningless.
+    WrongProgram {
+        expected: String,
+        expected_program: Pubkey,
+        observed_program: Pubkey,
+        code: u32,
+        index: u8,
+    },
+
+    /// The transaction failed with a custom error code, but the logs don't show which
+    /// program returned it, so it can't be decoded with the expected program's error enum.
+    UnknownProgram {
+        expected: String,
+        expected_program: Pubkey,
+        code: u32,
+        index: u8,
+    },
+
+    /// No log entry contained the expected string.
+    ///
+    /// `index` is set when a specific log entry was checked.
+    LogNotFound {
+        expected: String,
+        index: Option<usize>,
+        logs: Vec<String>,
+    },
+
+    /// The log pattern is not a valid regular expression.
+    InvalidPattern { pattern: String, error: String },
+
+    /// No log entry matched the regular expression.
+    PatternNotFound { pattern: String, logs: Vec<String> },
+
+    /// An ordered sequence of log strings was not found.
+    ///
+    /// `matched` holds the log index at which each leading item was found; the item at
+    /// `matched.len()` is the first that could not be found after them.
+    LogSequenceBroken {
+        expected: Vec<String>,
+        matched: Vec<usize>,
+        logs: Vec<String>,
+    },
+
+    /// Log entries contained a string that should not have been logged.
+    ///
+    /// `matches` holds the index and text of each offending entry.
+    UnexpectedLog {
+        unexpected: String,
+        matches: Vec<(usize, String)>,
+    },
+
+    /// A different number of log entries contained the expected string.
+    ///
+    /// `matches` holds the index and text of each entry that did.
+    WrongLogCount {
+        expected: String,
+        expected_count: usize,
+        matches: Vec<(usize, String)>,
+    },
+
+    /// No log message within a scope (a program, a depth, an instruction) contained the
+    /// expected string.
+    ///
+    /// `logs` holds the messages of the scoped invocations, or `None` if there were none.
+    ScopedLogNotFound {
+        expected: String,
+        scope: String,
+        logs: Option<Vec<String>>,
+    },
+
+    /// No CPI matched the expected program, data, or accounts.
+    ///
+    /// `cpis` describes every CPI the transaction made.
+    CpiNotFound { expected: String, cpis: Vec<String> },
+
+    /// A top-level instruction made a different number of CPIs than expected.
+    ///
+    /// `cpis` describes each CPI the instruction made.
+    WrongCpiCount {
+        index: u8,
+        expected_count: usize,
+        cpis: Vec<String>,
+    },
+
+    /// The programs invoked via CPI differ from the expected sequence.
+    WrongCpiSequence {
+        expected: Vec<Pubkey>,
+        observed: Vec<Pubkey>,
+    },
+
+    /// A top-level instruction's inner instructions don't line up with its logged CPIs, so
+    /// their programs can't be named.
+    ///
+    /// `recorded` and `logged` are the depths of the CPIs in each, in execution order.
+    CpiLogMismatch {
+        index: u8,
+        recorded: Vec<usize>,
+        logged: Vec<usize>,
+    },
+
+    /// No program set return data, or the last one set it empty.
+    ReturnDataMissing { expected: String },
+
+    /// The return data came from a different program, held different bytes, didn't decode,
+    /// or decoded to a value that didn't satisfy the predicate.
+    ///
+    /// `error` is the decoding error, if decoding failed.
+    ReturnDataMismatch {
+        expected: String,
+        program_id: Pubkey,
+        data: Vec<u8>,
+        error: Option<String>,
+    },
+
+    /// No emitted event of the expected type satisfied the predicate.
+    ///
+    /// `emitted` is how many events of that type were decoded.
+    EventNotFound { event: String, emitted: usize },
+
+    /// Events of a type that should not have been emitted were.
+    UnexpectedEvent { event: String, emitted: usize },
+
+    /// The account does not exist.
+    AccountNotFound { pubkey: Pubkey },
+
+    /// The account was expected to be closed but still holds lamports.
+    AccountNotClosed { pubkey: Pubkey, lamports: u64 },
+
+    /// The account isn't an SPL Token or Token-2022 account of the expected kind.
+    ///
+    /// `expected` is `"token account"` or `"mint"`.
+    NotTokenState {
+        pubkey: Pubkey,
+        expected: &'static str,
+        reason: String,
+    },
+
+    /// A scalar account field (lamports, owner, data length, executable, token state) has the
+    /// wrong value.
+    AccountMismatch {
+        pubkey: Pubkey,
+        field: &'static str,
+        expected: String,
+        observed: String,
+    },
+
+    /// Account data differs from the expected bytes.
+    ///
+    /// `observed` is the account data over the compared range, starting at `offset`.
+    DataMismatch {
+        pubkey: Pubkey,
+        offset: usize,
+        expected: Vec<u8>,
+        observed: Vec<u8>,
+    },
+
+    /// A transaction or instruction consumed compute units outside the expected range.
+    ///
+    /// `index` is set when a single top-level instruction was checked.
+    ComputeUnits {
+        expected: String,
+        observed: u64,
+        index: Option<u8>,
+    },
+
+    /// No `consumed X of Y compute units` log line was found for the instruction.
+    ///
+    /// `instructions` is how many top-level instructions were logged.
+    ComputeUnitsUnavailable { index: u8, instructions: usize },
+
+    /// One or more tracked balances changed by a different amount than expected.
+    ///
+    /// `rows` holds every tracked balance, matching or not, so the failure shows the whole
+    /// picture.
+    BalanceMismatch { rows: Vec<BalanceRow> },
+
+    /// A balance expectation named an account the tracker didn't snapshot.
+    UntrackedAccount { pubkey: Pubkey },
+
+    /// No log snapshot has been recorded under `name`.
+    ///
+    /// `actual` holds the normalized logs that would have been written to `path`.
+    SnapshotMissing {
+        name: String,
+        path: String,
+        actual: Vec<String>,
+    },
+
+    /// The normalized logs differ from the recorded snapshot.
+    SnapshotMismatch {
+        name: String,
+        path: String,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+
+    /// A log snapshot couldn't be read or written.
+    SnapshotIo { path: String, error: String },
+}
+
+impl fmt::Display for DemandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DemandError::UnexpectedSuccess {
+                expected,
+                expected_index: None,
+            } => write!(f, "Expected {} but transaction succeeded", expected),
+
+            DemandError::UnexpectedSuccess {
+                expected,
+                expected_index: Some(index),
+            } => write!(
+                f,
+                "Expected {} at index {} but transaction succeeded",
+                expected, index
+            ),
+
+            DemandError::WrongIndex {
+                expected,
+                expected_index,
+                observed_index,
+            } => write!(
+                f,
+                "Expected {} at index {} but got error at index {}",
+                expected, expected_index, observed_index
+            ),
+
+            DemandError::WrongError {
+                expected,
+                observed,
+                index: None,
+            } => write!(f, "Expected {} but got {}", expected, observed),
+
+            DemandError::WrongError {
+                expected,
+                observed,
+                index: Some(index),
+            } => write!(
+                f,
+                "Expected {} but got {} at index {}",
+                expected, observed, index
+            ),
+
+            DemandError::WrongProgram {
+                expected,
+                expected_program,
+                observed_program,
+                code,
+                index,
+            } => {
+                let observed = DecodedError {
+                    index: *index,
+                    code: *code,
+                    program_id: None,
+                    name: error_name(observed_program, *code),
+                };
+                write!(
+                    f,
+                    "Expected {} from {} but got {} at index {} from {}",
+                    expected,
+                    address_name(expected_program),
+                    observed.name_and_code(),
+                    index,
+                    address_name(observed_program)
+                )
+            }
+
+            DemandError::UnknownProgram {
+                expected,
+                expected_program,
+                code,
+                index,
+            } => write!(
+                f,
+                "Expected {} from {} but got custom program error {:#x} at index {} from a \
+                 program the logs don't name",
+                expected,
+                address_name(expected_program),
+                code,
+                index
+            ),
+
+            DemandError::LogNotFound {
+                expected,
+                index: None,
+                logs,
+            } => write!(
+                f,
+                "Expected {:?} among {} log entries: {}",
+                expected,
+                logs.len(),
+                logs.iter()
+                    .enumerate()
+                    .map(|(i, log)| format!("[{}]: {}", i, log))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            DemandError::LogNotFound {
+                expected: _,
+                index: Some(index),
+                logs,
+            } if *index >= logs.len() => write!(
+                f,
+                "Log index {} out of bounds, only {} entries available",
+                index,
+                logs.len()
+            ),
+
+            DemandError::LogNotFound {
+                expected,
+                index: Some(index),
+                logs,
+            } => write!(
+                f,
+                "Expected {:?} at log index {} but found: {:?}",
+                expected, index, logs[*index]
+            ),
+
+            DemandError::InvalidPattern { pattern, error } => {
+                write!(f, "Invalid log pattern {:?}: {}", pattern, error)
+            }
+
+            DemandError::PatternNotFound { pattern, logs } => write!(
+                f,
+                "Expected a log entry matching /{}/ among {} log entries: {}",
+                pattern,
+                logs.len(),
+                logs.iter()
+                    .enumerate()
+                    .map(|(i, log)| format!("[{}]: {}", i, log))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            DemandError::LogSequenceBroken {
+                expected,
+                matched,
+                logs,
+            } => {
+                let broken = matched.len();
+                let after = match matched.last() {
+                    Some(index) => format!("after log index {}", index),
+                    None => "in the logs".to_string(),
+                };
+
+                writeln!(
+                    f,
+                    "Expected log sequence broke at item {} of {}: {:?} not found {}",
+                    broken,
+                    expected.len(),
+                    expected[broken],
+                    after
+                )?;
+                for (item, log_index) in expected.iter().zip(matched) {
+                    writeln!(f, "  matched {:?} at log index {}", item, log_index)?;
+                }
+                writeln!(f, "  missing {:?}", expected[broken])?;
+                for item in &expected[broken + 1..] {
+                    writeln!(f, "  not checked {:?}", item)?;
+                }
+                write!(f, "{} log entries:", logs.len())?;
+                for (i, log) in logs.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, log)?;
+                }
+                Ok(())
+            }
+
+            DemandError::UnexpectedLog {
+                unexpected,
+                matches,
+            } => write!(
+                f,
+                "Expected no log entry containing {:?} but found {}: {}",
+                unexpected,
+                matches.len(),
+                matches
+                    .iter()
+                    .map(|(i, log)| format!("[{}]: {}", i, log))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            DemandError::WrongLogCount {
+                expected,
+                expected_count,
+                matches,
+            } => write!(
+                f,
+                "Expected {} log entries containing {:?} but found {}{}{}",
+                expected_count,
+                expected,
+                matches.len(),
+                if matches.is_empty() { "" } else { ": " },
+                matches
+                    .iter()
+                    .map(|(i, log)| format!("[{}]: {}", i, log))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            DemandError::ScopedLogNotFound {
+                expected,
+                scope,
+                logs: None,
+            } => write!(
+                f,
+                "Expected {:?} in the logs of {} but no such invocation was found",
+                expected, scope
+            ),
+
+            DemandError::ScopedLogNotFound {
+                expected,
+                scope,
+                logs: Some(logs),
+            } => write!(
+                f,
+                "Expected {:?} among {} log messages of {}: {}",
+                expected,
+                logs.len(),
+                scope,
+                logs.iter()
+                    .enumerate()
+                    .map(|(i, log)| format!("[{}]: {}", i, log))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            DemandError::EventNotFound { event, emitted: 0 } => {
+                write!(f, "Expected a {} event but none was emitted", event)
+            }
+
+            DemandError::EventNotFound { event, emitted } => write!(
+                f,
+                "Expected a {} event matching the predicate but none of the {} emitted matched",
+                event, emitted
+            ),
+
+            DemandError::UnexpectedEvent { event, emitted } => write!(
+                f,
+                "Expected no {} event but {} were emitted",
+                event, emitted
+            ),
+
+            DemandError::AccountNotFound { pubkey } => {
+                write!(
+                    f,
+                    "Expected account {} to exist but it was not found",
+                    address_name(pubkey)
+                )
+            }
+
+            DemandError::AccountNotClosed { pubkey, lamports } => write!(
+                f,
+                "Expected account {} to be closed but it holds {} lamports",
+                address_name(pubkey),
+                lamports
+            ),
+
+            DemandError::NotTokenState {
+                pubkey,
+                expected,
+                reason,
+            } => write!(
+                f,
+                "Expected account {} to be a {} but {}",
+                address_name(pubkey),
+                expected,
+                reason
+            ),
+
+            DemandError::AccountMismatch {
+                pubkey,
+                field,
+                expected,
+                observed,
+            } => write!(
+                f,
+                "Expected {} of account {} to be {} but got {}",
+                field,
+                address_name(pubkey),
+                expected,
+                observed
+            ),
+
+            DemandError::DataMismatch {
+                pubkey,
+                offset,
+                expected,
+                observed,
+            } => {
+                writeln!(
+                    f,
+                    "Expected {} bytes of account {} data at offset {} but got {} bytes:",
+                    expected.len(),
+                    address_name(pubkey),
+                    offset,
+                    observed.len()
+                )?;
+                write!(f, "{}", hex_diff(*offset, expected, observed))
+            }
+
+            DemandError::ComputeUnits {
+                expected,
+                observed,
+                index: None,
+            } => write!(
+                f,
+                "Expected compute units {} but transaction consumed {}",
+                expected, observed
+            ),
+
+            DemandError::ComputeUnits {
+                expected,
+                observed,
+                index: Some(index),
+            } => write!(
+                f,
+                "Expected compute units {} at index {} but instruction consumed {}",
+                expected, index, observed
+            ),
+
+            DemandError::ComputeUnitsUnavailable {
+                index,
+                instructions,
+            } => write!(
+                f,
+                "No compute unit log for instruction at index {} ({} top-level instructions logged)",
+                index, instructions
+            ),
+
+            DemandError::BalanceMismatch { rows } => {
+                writeln!(f, "Expected balance deltas did not match:")?;
+                write!(f, "{}", balance_table(rows))
+            }
+
+            DemandError::UntrackedAccount { pubkey } => write!(
+                f,
+                "Expected a balance delta for account {} but the tracker didn't snapshot it",
+                address_name(pubkey)
+            ),
+
+            DemandError::CpiNotFound { expected, cpis } if cpis.is_empty() => {
+                write!(f, "Expected {} but the transaction made no CPIs", expected)
+            }
+
+            DemandError::CpiNotFound { expected, cpis } => {
+                write!(f, "Expected {} among {} CPIs:", expected, cpis.len())?;
+                for (i, cpi) in cpis.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, cpi)?;
+                }
+                Ok(())
+            }
+
+            DemandError::WrongCpiCount {
+                index,
+                expected_count,
+                cpis,
+            } => {
+                write!(
+                    f,
+                    "Expected {} CPIs from instruction {} but found {}",
+                    expected_count,
+                    index,
+                    cpis.len()
+                )?;
+                for (i, cpi) in cpis.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, cpi)?;
+                }
+                Ok(())
+            }
+
+            DemandError::WrongCpiSequence { expected, observed } => {
+                write!(f, "Expected CPI sequence:")?;
+                for (i, program_id) in expected.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, address_name(program_id))?;
+                }
+                write!(f, "\nbut observed:")?;
+                for (i, program_id) in observed.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, address_name(program_id))?;
+                }
+                Ok(())
+            }
+
+            DemandError::CpiLogMismatch {
+                index,
+                recorded,
+                logged,
+            } => write!(
+                f,
+                "Can't name the programs of instruction {}'s CPIs: the runtime recorded CPIs \
+                 at depths {:?} but the logs show {:?} (were the logs truncated?)",
+                index, recorded, logged
+            ),
+
+            DemandError::ReturnDataMissing { expected } => {
+                write!(f, "Expected {} but no return data was set", expected)
+            }
+
+            DemandError::ReturnDataMismatch {
+                expected,
+                program_id,
+                data,
+                error,
+            } => {
+                write!(
+                    f,
+                    "Expected {} but {} returned {}",
+                    expected,
+                    address_name(program_id),
+                    hex(data)
+                )?;
+                if let Some(error) = error {
+                    write!(f, " ({})", error)?;
+                }
+                Ok(())
+            }
+
+            DemandError::SnapshotMissing { name, path, actual } => {
+                write!(
+                    f,
+                    "No log snapshot \"{}\" at {} (set {}=1 to record it), normalized logs:",
+                    name, path, UPDATE_SNAPSHOTS_ENV
+                )?;
+                for (i, log) in actual.iter().enumerate() {
+                    write!(f, "\n  [{}]: {}", i, log)?;
+                }
+                Ok(())
+            }
+
+            DemandError::SnapshotMismatch {
+                name,
+                path,
+                expected,
+                actual,
+            } => {
+                writeln!(
+                    f,
+                    "Log snapshot \"{}\" at {} does not match (set {}=1 to accept), - snapshot + actual:",
+                    name, path, UPDATE_SNAPSHOTS_ENV
+                )?;
+                write!(f, "{}", line_diff(expected, actual))
+            }
+
+            DemandError::SnapshotIo { path, error } => {
+                write!(f, "Failed to access log snapshot {}: {}", path, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DemandError {}
+
+/// Renders expected and observed bytes as 16-byte hex rows, marking differing bytes with `^^`.
+///
+/// Rows where both sides agree are elided. Bytes missing from one side render as `--`.
+fn hex_diff(offset: usize, expected: &[u8], observed: &[u8]) -> String {
+    const ROW: usize = 16;
+
+    let len = expected.len().max(observed.len());
+    let cell = |bytes: &[u8], i: usize| {
+        bytes
+            .get(i)
+            .map(|b| format!("{:02x}", b))
+            .unwrap_or_else(|| "--".to_string())
+    };
+
+    let mut out = Vec::new();
+    for row in (0..len).step_by(ROW) {
+        let range = row..(row + ROW).min(len);
+        if range.clone().all(|i| expected.get(i) == observed.get(i)) {
+            continue;
+        }
+
+        let line = |bytes: &[u8]| range.clone().map(|i| cell(bytes, i)).collect::<Vec<_>>();
+        let markers = range
+            .clone()
+            .map(|i| {
+                if expected.get(i) == observed.get(i) {
+                    "  "
+                } else {
+                    "^^"
+                }
+            })
+            .collect::<Vec<_>>();
+
+        out.push(format!(
+            "  {:06x}  expected: {}",
+            offset + row,
+            line(expected).join(" ")
+        ));
+        out.push(format!("          observed: {}", line(observed).join(" ")));
+        out.push(format!(
+            "                    {}",
+            markers.join(" ").trim_end()
+        ));
+    }
+
+    out.join("\n")
+}
+
+/// Renders balance rows as an aligned table, marking rows whose deltas differ with `<-`.
+fn balance_table(rows: &[BalanceRow]) -> String {
+    let signed = |delta: i128| match delta {
+        0 => "0".to_string(),
+        _ => format!("{:+}", delta),
+    };
+
+    let mut out = vec![format!(
+        "  {:<44}  {:<8}  {:>14}  {:>14}",
+        "account", "asset", "expected", "actual"
+    )];
+
+    for row in rows {
+        let line = format!(
+            "  {:<44}  {:<8}  {:>14}  {:>14}",
+            address_name(&row.pubkey),
+            row.asset,
+            signed(row.expected),
+            signed(row.actual)
+        );
+        out.push(if row.matches() {
+            line
+        } else {
+            format!("{}  <-", line)
+        });
+    }
+
+    out.join("\n")
+}
+
+/// Renders a line diff of `expected` against `actual`, prefixing removed lines with `-`,
+/// added lines with `+`, and unchanged lines with a space.
+fn line_diff(expected: &[String], actual: &[String]) -> String {
+    let (n, m) = (expected.len(), actual.len());
+
+    // lcs[i][j] is the longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            out.push(format!("    {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push(format!("  - {}", expected[i]));
+            i += 1;
+        } else {
+            out.push(format!("  + {}", actual[j]));
+            j += 1;
+        }
+    }
+
+    out.join("\n")
+}
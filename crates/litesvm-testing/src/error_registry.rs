@@ -0,0 +1,408 @@
+//! Program-aware decoding of custom program errors.
+//!
+//! A failed instruction only carries `InstructionError::Custom(code)`; what the code means
+//! depends on which program returned it. This module keeps a registry of error decoders by
+//! program id, and uses the logs to find the program that failed:
+//!
+//! ```text
+//! custom program error: 0x1                            // what the runtime reports
+//! spl_token::InsufficientFunds (0x1) in instruction 2  // what decode_error reports
+//! ```
+//!
+//! Decoders for the system program, SPL Token, Token-2022 and the associated token account
+//! program are built in. With the `anchor` feature, Anchor framework errors are decoded
+//! from the program's `AnchorError` log line, whichever program logged it, as are the
+//! variant names of unregistered Anchor programs' own `#[error_code]` enums. Register your
+//! own program's error enum once, e.g. at the start of a test:
+//!
+//! ```text
+//! register_program_errors::<VaultError>(vault::ID, "vault");
+//! ```
+//!
+//! The registry is shared by every test in the process. The error assertions use it to
+//! describe the error they observed.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, RwLock},
+};
+
+use litesvm::types::TransactionResult;
+use num_traits::FromPrimitive;
+use solana_instruction::error::InstructionError;
+use solana_pubkey::Pubkey;
+use solana_system_interface::{error::SystemError, program as system_program};
+use solana_transaction_error::TransactionError;
+use spl_associated_token_account::error::AssociatedTokenAccountError;
+
+use crate::{log_parser::failed_program, TOKEN_2022_PROGRAM_ID};
+
+type DecodeFn = Box<dyn Fn(u32) -> Option<String> + Send + Sync>;
+
+struct ProgramErrors {
+    label: String,
+    decode: DecodeFn,
+}
+
+fn registry() -> &'static RwLock<HashMap<Pubkey, ProgramErrors>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<Pubkey, ProgramErrors>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| {
+        let mut builtins = HashMap::new();
+        let mut add = |program_id: Pubkey, label: &str, decode: DecodeFn| {
+            let label = label.to_string();
+            builtins.insert(program_id, ProgramErrors { label, decode });
+        };
+
+        add(system_program::ID, "system", debug_name::<SystemError>());
+        add(
+            spl_token::ID,
+            "spl_token",
+            debug_name::<spl_token::error::TokenError>(),
+        );
+        add(
+            TOKEN_2022_PROGRAM_ID,
+            "spl_token_2022",
+            Box::new(|code| {
+                TOKEN_2022_ERRORS
+                    .get(code as usize)
+                    .map(|name| name.to_string())
+            }),
+        );
+        add(
+            spl_associated_token_account::ID,
+            "spl_associated_token_account",
+            debug_name::<AssociatedTokenAccountError>(),
+        );
+
+        RwLock::new(builtins)
+    })
+}
+
+fn debug_name<E: FromPrimitive + fmt::Debug>() -> DecodeFn {
+    Box::new(|code| E::from_u32(code).map(|error| format!("{:?}", error)))
+}
+
+/// Token-2022's `TokenError` variants, by code, so they decode without the optional
+/// `spl-token-2022` dependency.
+const TOKEN_2022_ERRORS: [&str; 69] = [
+    "NotRentExempt",
+    "InsufficientFunds",
+    "InvalidMint",
+    "MintMismatch",
+    "OwnerMismatch",
+    "FixedSupply",
+    "AlreadyInUse",
+    "InvalidNumberOfProvidedSigners",
+    "InvalidNumberOfRequiredSigners",
+    "UninitializedState",
+    "NativeNotSupported",
+    "NonNativeHasBalance",
+    "InvalidInstruction",
+    "InvalidState",
+    "Overflow",
+    "AuthorityTypeNotSupported",
+    "MintCannotFreeze",
+    "AccountFrozen",
+    "MintDecimalsMismatch",
+    "NonNativeNotSupported",
+    "ExtensionTypeMismatch",
+    "ExtensionBaseMismatch",
+    "ExtensionAlreadyInitialized",
+    "ConfidentialTransferAccountHasBalance",
+    "ConfidentialTransferAccountNotApproved",
+    "ConfidentialTransferDepositsAndTransfersDisabled",
+    "ConfidentialTransferElGamalPubkeyMismatch",
+    "ConfidentialTransferBalanceMismatch",
+    "MintHasSupply",
+    "NoAuthorityExists",
+    "TransferFeeExceedsMaximum",
+    "MintRequiredForTransfer",
+    "FeeMismatch",
+    "FeeParametersMismatch",
+    "ImmutableOwner",
+    "AccountHasWithheldTransferFees",
+    "NoMemo",
+    "NonTransferable",
+    "NonTransferableNeedsImmutableOwnership",
+    "MaximumPendingBalanceCreditCounterExceeded",
+    "MaximumDepositAmountExceeded",
+    "CpiGuardSettingsLocked",
+    "CpiGuardTransferBlocked",
+    "CpiGuardBurnBlocked",
+    "CpiGuardCloseAccountBlocked",
+    "CpiGuardApproveBlocked",
+    "CpiGuardSetAuthorityBlocked",
+    "CpiGuardOwnerChangeBlocked",
+    "ExtensionNotFound",
+    "NonConfidentialTransfersDisabled",
+    "ConfidentialTransferFeeAccountHasWithheldFee",
+    "InvalidExtensionCombination",
+    "InvalidLengthForAlloc",
+    "AccountDecryption",
+    "ProofGeneration",
+    "InvalidProofInstructionOffset",
+    "HarvestToMintDisabled",
+    "SplitProofContextStateAccountsNotSupported",
+    "NotEnoughProofContextStateAccounts",
+    "MalformedCiphertext",
+    "CiphertextArithmeticFailed",
+    "PedersenCommitmentMismatch",
+    "RangeProofLengthMismatch",
+    "IllegalBitLength",
+    "FeeCalculation",
+    "IllegalMintBurnConversion",
+    "InvalidScale",
+    "MintPaused",
+    "PendingBalanceNonZero",
+];
+
+/// Registers a program's error enum, so its custom codes are decoded to variant names.
+///
+/// `label` prefixes the variant name, as in `vault::InsufficientFunds`. Registering a
+/// program again replaces its decoder, including a built-in one.
+///
+/// # Example
+///
+/// ```text
+/// #[derive(Debug, FromPrimitive)]
+/// enum VaultError { InsufficientFunds, Locked }
+///
+/// register_program_errors::<VaultError>(vault::ID, "vault");
+/// ```
+pub fn register_program_errors<E: FromPrimitive + fmt::Debug>(program_id: Pubkey, label: &str) {
+    register_error_decoder(program_id, label, debug_name::<E>());
+}
+
+/// Registers a function that names a program's custom error codes.
+///
+/// For programs whose errors aren't a `FromPrimitive` enum. `decode` returns `None` for
+/// codes it doesn't know.
+///
+/// # Example
+///
+/// ```text
+/// register_error_decoder(oracle::ID, "oracle", |code| match code {
+///     0x10 => Some("StalePrice".to_string()),
+///     _ => None,
+/// });
+/// ```
+pub fn register_error_decoder(
+    program_id: Pubkey,
+    label: &str,
+    decode: impl Fn(u32) -> Option<String> + Send + Sync + 'static,
+) {
+    let errors = ProgramErrors {
+        label: label.to_string(),
+        decode: Box::new(decode),
+    };
+    registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(program_id, errors);
+}
+
+/// Names a program's custom error code as `label::Variant`, using the registered decoder.
+///
+/// Returns `None` if no decoder is registered for the program, or it doesn't know the code.
+pub fn error_name(program_id: &Pubkey, code: u32) -> Option<String> {
+    let registry = registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let errors = registry.get(program_id)?;
+    let name = (errors.decode)(code)?;
+    Some(format!("{}::{}", errors.label, name))
+}
+
+/// A failed transaction's custom program error, attributed to the program that returned it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedError {
+    /// The top-level instruction that failed.
+    pub index: u8,
+
+    /// The custom error code.
+    pub code: u32,
+
+    /// The program that returned the code, if the logs identify it.
+    pub program_id: Option<Pubkey>,
+
+    /// The code's name, e.g. `spl_token::InsufficientFunds`, if a decoder knows it.
+    pub name: Option<String>,
+}
+
+impl DecodedError {
+    /// The error without its instruction index, e.g. `spl_token::InsufficientFunds (0x1)`.
+    pub fn name_and_code(&self) -> String {
+        match (&self.name, &self.program_id) {
+            (Some(name), _) => format!("{} ({:#x})", name, self.code),
+            (None, Some(program_id)) => {
+                format!("custom program error {:#x} from {}", self.code, program_id)
+            }
+            (None, None) => format!("custom program error {:#x}", self.code),
+        }
+    }
+}
+
+impl fmt::Display for DecodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in instruction {}", self.name_and_code(), self.index)
+    }
+}
+
+/// Decodes a failed transaction's custom program error.
+///
+/// Returns `None` if the transaction succeeded, or failed with anything other than
+/// `InstructionError::Custom`.
+pub fn decode_error(result: &TransactionResult) -> Option<DecodedError> {
+    let Err(e) = result else {
+        return None;
+    };
+    let TransactionError::InstructionError(index, InstructionError::Custom(code)) = e.err else {
+        return None;
+    };
+
+    let program_id = failed_program(result);
+    let name = program_id
+        .and_then(|program_id| error_name(&program_id, code))
+        .or_else(|| anchor_error_name(code, result));
+
+    Some(DecodedError {
+        index,
+        code,
+        program_id,
+        name,
+    })
+}
+
+#[cfg(feature = "anchor")]
+fn anchor_error_name(code: u32, result: &TransactionResult) -> Option<String> {
+    let log = crate::anchor_testing::anchor_error_log(result)?;
+    if log.error_number != code {
+        return None;
+    }
+
+    if code < anchor_lang::error::ERROR_CODE_OFFSET {
+        Some(format!("anchor::{}", log.error_code))
+    } else {
+        Some(log.error_code)
+    }
+}
+
+#[cfg(not(feature = "anchor"))]
+fn anchor_error_name(_code: u32, _result: &TransactionResult) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::FailedTransactionMetadata;
+
+    use super::*;
+
+    fn failed(index: u8, code: u32, logs: &[String]) -> FailedTransactionMetadata {
+        let mut failed = FailedTransactionMetadata {
+            err: TransactionError::InstructionError(index, InstructionError::Custom(code)),
+            meta: Default::default(),
+        };
+        failed.meta.logs = logs.to_vec();
+        failed
+    }
+
+    fn failed_by(program_id: &Pubkey, code: u32) -> Vec<String> {
+        vec![
+            format!("Program {} invoke [1]", program_id),
+            format!(
+                "Program {} failed: custom program error: {:#x}",
+                program_id, code
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_builtin_decoders() {
+        let result = Err(failed(2, 1, &failed_by(&spl_token::ID, 1)));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            "spl_token::InsufficientFunds (0x1) in instruction 2"
+        );
+
+        let result = Err(failed(0, 1, &failed_by(&system_program::ID, 1)));
+        assert_eq!(
+            decode_error(&result).unwrap().name.as_deref(),
+            Some("system::ResultWithNegativeLamports")
+        );
+
+        let result = Err(failed(1, 0x43, &failed_by(&TOKEN_2022_PROGRAM_ID, 0x43)));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            "spl_token_2022::MintPaused (0x43) in instruction 1"
+        );
+    }
+
+    #[cfg(feature = "token")]
+    #[test]
+    fn test_token_2022_names_match_the_program() {
+        use spl_token_2022::error::TokenError;
+
+        assert_eq!(TOKEN_2022_PROGRAM_ID, spl_token_2022::ID);
+        for (code, name) in TOKEN_2022_ERRORS.iter().enumerate() {
+            let error = TokenError::from_u32(code as u32).unwrap();
+            assert_eq!(format!("{:?}", error), *name);
+        }
+        assert!(TokenError::from_u32(TOKEN_2022_ERRORS.len() as u32).is_none());
+    }
+
+    #[test]
+    fn test_registered_decoder() {
+        let program_id = Pubkey::new_unique();
+        register_error_decoder(program_id, "oracle", |code| {
+            (code == 0x10).then(|| "StalePrice".to_string())
+        });
+
+        let result = Err(failed(1, 0x10, &failed_by(&program_id, 0x10)));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            "oracle::StalePrice (0x10) in instruction 1"
+        );
+
+        let result = Err(failed(1, 0x11, &failed_by(&program_id, 0x11)));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            format!(
+                "custom program error 0x11 from {} in instruction 1",
+                program_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_unknown_program() {
+        // Without logs, the failing program is unknown
+        let result = Err(failed(3, 6, &[]));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            "custom program error 0x6 in instruction 3"
+        );
+        assert_eq!(decode_error(&Ok(Default::default())), None);
+    }
+
+    #[cfg(feature = "anchor")]
+    #[test]
+    fn test_anchor_framework_errors() {
+        let program_id = Pubkey::new_unique();
+        let mut logs = failed_by(&program_id, 2000);
+        logs.insert(
+            1,
+            "Program log: AnchorError caused by account: vault. Error Code: ConstraintMut. \
+             Error Number: 2000. Error Message: A mut constraint was violated."
+                .to_string(),
+        );
+
+        let result = Err(failed(0, 2000, &logs));
+        assert_eq!(
+            decode_error(&result).unwrap().to_string(),
+            "anchor::ConstraintMut (0x7d0) in instruction 0"
+        );
+    }
+}
@@ -21,10 +21,25 @@
 //! demand_system_error(SystemError::ResultWithNegativeLamports, result);
 //! ```
 //!
-//! **Fluent Methods** (chainable):
+//! **Fluent Methods**:
 //! ```text
-//! result.demand_logs_contain("Hello!")
-//!       .demand_system_error(SystemError::ResultWithNegativeLamports);
+//! result.demand_system_error(SystemError::ResultWithNegativeLamports);
+//! ```
+//!
+//! **Chained Methods** (several assertions on one result):
+//! ```text
+//! result.demand_system_error_and(SystemError::ResultWithNegativeLamports)
+//!       .demand_logs_contain_and("insufficient lamports")
+//!       .accept();
+//! ```
+//!
+//! **Non-panicking Checks** (inspect failures instead of panicking):
+//! ```text
+//! if let Err(DemandError::WrongIndex { observed_index, .. }) =
+//!     check_system_error_at_index(1, SystemError::ResultWithNegativeLamports, &result)
+//! {
+//!     // ...
+//! }
 //! ```
 //!
 //! ## Error Testing Hierarchy
@@ -40,6 +55,177 @@
 //! - `demand_system_error(SystemError::ResultWithNegativeLamports, result)` (anywhere)
 //! - `demand_system_error_at_index(1, SystemError::ResultWithNegativeLamports, result)` (surgical)
 //!
+//! **🪙 Token Level**: Type-safe SPL Token and associated token account program errors
+//! - `demand_token_error(TokenError::InsufficientFunds, result)` (anywhere)
+//! - `demand_associated_token_error_at_index(1, AssociatedTokenAccountError::InvalidOwner, result)` (surgical)
+//!
+//! System, token, and ATA errors only match when the logs show that program raised the
+//! custom code, directly or via CPI; a different program's code fails with
+//! [`DemandError::WrongProgram`] instead of being decoded with the wrong enum, and a code the
+//! logs don't attribute to any program fails with [`DemandError::UnknownProgram`].
+//!
+//! **🧩 Program Level**: Your program's own error enum (Pinocchio, native), decoded through
+//! `FromPrimitive` and encoded through `Into<u32>`
+//! - `demand_program_error(VaultError::InsufficientFunds, result)` (anywhere)
+//! - `demand_program_error_at_index(1, VaultError::InsufficientFunds, result)` (surgical)
+//!
+//! **🔎 Decoded Errors**: [`error_registry`] names custom program errors by the program
+//! that returned them, so failures read `spl_token::InsufficientFunds (0x1) at index 2`
+//! rather than `custom program error: 0x1`
+//! - System, SPL Token, Token-2022, ATA and (`anchor` feature) Anchor framework errors are built in
+//! - `register_program_errors::<VaultError>(vault::ID, "vault")` adds your own
+//! - `decode_error(&result)` decodes a failed transaction's error directly
+//!
+//! **⚓ Anchor Level** (`anchor` feature): `#[error_code]` enums and framework `ErrorCode`s,
+//! matched against both the custom error code and the `AnchorError` log line
+//! - `demand_anchor_error(MyError::Unauthorized, result)` (anywhere)
+//! - `demand_anchor_error_at_index(1, ErrorCode::ConstraintMut, result)` (surgical)
+//!
+//! ## Account State
+//!
+//! [`account_testing`] asserts on what a transaction left behind, reading accounts from the
+//! `LiteSVM` instance:
+//! - `demand_account_exists(&svm, &pubkey)` / `demand_account_closed(&svm, &pubkey)`
+//! - `demand_lamports`, `demand_owner`, `demand_data_len`, `demand_executable`
+//! - `demand_data_eq` / `demand_data_range_eq` with a hex diff on mismatch
+//!
+//! [`account_builder`] goes the other way, writing accounts into the runtime without a
+//! transaction. Lamports default to the rent-exempt minimum for the data:
+//! - `AccountBuilder::new().owner(&program_id).borsh(&config).write(&mut svm, &pubkey)`
+//! - `.data(bytes)` / `.pack(&state)` / `.anchor(&account)` (`anchor` feature) / `.executable(true)`
+//! - `AccountBuilder::mint(..)` / `AccountBuilder::token_account(..)` (`token` feature)
+//!
+//! [`account_fixtures`] loads accounts dumped with `solana account <pubkey> --output json`,
+//! and exports accounts back to the same format:
+//! - `load_account_fixture(&mut svm, "tests/fixtures/pool.json")` /
+//!   `load_account_fixture_dir(&mut svm, "tests/fixtures/")`
+//! - `export_account_fixture(&svm, &pool, "tests/fixtures/pool.after.json")`
+//!
+//! ## Structured Logs
+//!
+//! [`log_parser`] turns a transaction's flat log list into a tree of `ProgramInvocation`s -
+//! program id, CPI depth, `Program log:` messages, `Program data:` payloads, compute units,
+//! return data, outcome, and child invocations:
+//! - `parse_logs(&result)` - one tree per top-level instruction
+//! - `failed_program(&result)` - the innermost program that failed, whose error enum applies
+//!
+//! [`log_testing`] builds on it to scope log assertions, so they survive added CPIs and log
+//! lines:
+//! - `demand_program_logged(&program_id, "Transfer", result)` - any depth
+//! - `demand_logged_at_depth(&program_id, 2, "Transfer", result)` - only via CPI
+//! - `demand_instruction_logged(1, "Transfer", result)` - inside one top-level instruction
+//!
+//! It also matches patterns beyond a single substring:
+//! - `demand_logs_match(r"Transferred \d+ tokens", result)` - regular expressions
+//! - `demand_logs_in_order(&["Deposit", "Transfer"], result)` - ordered subsequences
+//! - `demand_logs_not_contain("Error", result)` / `demand_log_count("Transfer", 2, result)`
+//!
+//! ## Cross-Program Invocations
+//!
+//! [`cpi_testing`] asserts on the instructions programs invoked via CPI, read from the
+//! transaction's inner instructions:
+//! - `demand_cpi_invoked(&spl_token::ID, result)` / `demand_cpi_count(1, 2, result)`
+//! - `demand_cpi_sequence(&[spl_token::ID, system_program::ID], result)` - exact order
+//! - `demand_cpi(&CpiMatcher::new(spl_token::ID).data_prefix(&[12]).accounts(&tx, &accounts), result)`
+//!   - the right instruction, on the right accounts
+//!
+//! ## Return Data
+//!
+//! [`return_data_testing`] asserts on the data a program passed to `set_return_data`:
+//! - `demand_return_data(&program_id, &42u64.to_le_bytes(), result)` - exact bytes
+//! - `demand_return_data_decodes::<Quote>(|q| q.amount_out >= 1_000, result)` - Borsh-decoded
+//! - `anchor_testing::return_value::<T>(&result)` - an Anchor view's declared return type
+//!   (`anchor` feature)
+//!
+//! ## Log Snapshots
+//!
+//! [`snapshot_testing`] locks down a transaction's whole log against a checked-in file under
+//! `tests/snapshots/`, after aliasing pubkeys, hashing base64 payloads and optionally masking
+//! compute units:
+//! - `demand_logs_snapshot("deposit", result)`
+//! - `LogSnapshot::new().alias(vault, "vault").mask_compute_units().demand("deposit", result)`
+//! - `LITESVM_UPDATE_SNAPSHOTS=1 cargo test` records new or changed snapshots
+//!
+//! ## Failure Reports
+//!
+//! [`report`] renders a transaction result for humans: signature, status, decoded error, fee,
+//! compute units, the invocation tree with per-program logs and CU, inner instructions and
+//! return data. Every failing `demand_*` assertion on a result prints it after the failure
+//! message, unless `LITESVM_DESCRIBE_FAILURES=0`:
+//! - `describe(&result)`
+//! - `TransactionReport::new(&result).transaction(&tx).address_book(&book)` - with the fee and
+//!   named accounts
+//!
+//! ## Address Book
+//!
+//! [`address_book`] labels pubkeys for every piece of output: assertion failures, reports,
+//! log snapshots and `cu_bench` context discovery. Well-known programs, loaders and sysvars
+//! are built in:
+//! - `book.insert(alice, "alice")` / `book.insert_under(&alice, "usdc_ata", ata)` -
+//!   hierarchical labels like `alice.usdc_ata`
+//! - `book.pda(&program_id, &[b"vault", alice.as_ref()])` - derives and labels a PDA after its
+//!   seeds
+//! - `book.share()` - makes the labels available to assertion failures
+//!
+//! ## Token Fixtures
+//!
+//! [`token_testing`] (`token` feature) writes SPL Token and Token-2022 state into the
+//! runtime directly, with rent-exempt lamports, instead of sending setup transactions:
+//! - `let mint = create_mint(&mut svm, &authority, 6);`
+//! - `MintBuilder::new(&authority, 6).transfer_fee(100, 1_000_000).create(&mut svm)` - a
+//!   Token-2022 mint with extensions
+//! - `let ata = funded_token_account(&mut svm, &owner, &mint, 1_000_000);`
+//! - `create_ata(&mut svm, &owner, &mint)` / `mint_to(&mut svm, &mint, &ata, 500)`
+//!
+//! Its assertions read SPL Token and Token-2022 accounts alike, and print amounts in UI units:
+//! - `demand_token_balance(&svm, &ata, 1_000_000)` / `demand_mint_supply(&svm, &mint, 1_000_000)`
+//! - `demand_token_owner(&svm, &ata, &owner)` / `demand_delegate(&svm, &ata, &router, 500)`
+//! - `demand_frozen(&svm, &ata)`
+//! - `demand_withheld_fees(&svm, &ata, 100)` / `demand_transfer_hook(&svm, &mint, &hook)` /
+//!   `demand_has_extension(&svm, &mint, ExtensionType::MetadataPointer)` - Token-2022
+//!   extension state
+//!
+//! ## Test Context
+//!
+//! [`test_context`] wraps LiteSVM with a funded payer and named actors, and signs, pays for
+//! and sends transactions with a fresh blockhash each time:
+//! - `let mut ctx = TestContext::new().actor_balance(5 * LAMPORTS_PER_SOL);`
+//! - `let alice = ctx.actor("alice");` - a funded keypair, labeled in the context's address book
+//! - `ctx.send(&[ix], &[&alice]).demand_logs_contain("success")`
+//! - `ctx.report(&result)` - a report naming accounts from the context's labels
+//! - `TestContext::new().share_labels()` - also name them in assertion failures
+//! - `ctx.svm_mut()` - the inner runtime, for deploying programs and setting accounts
+//!
+//! Its keypairs come from [`seed`]: derived from a seed and a label, the same on every run.
+//! A failing test prints the seed; `LITESVM_SEED=<seed>` replays it:
+//! - `ctx.keypair("mint")` - a seeded, labeled keypair that isn't funded
+//! - `seeded_keypair(test_seed(), "sender")` - the same outside a context
+//! - `let seed = SeedGuard::new(test_seed());` - prints the seed if the test fails
+//!
+//! ## Compute Units
+//!
+//! [`cu_testing`] guards against CU regressions in ordinary tests, for successful and failed
+//! transactions alike:
+//! - `demand_cu_at_most(12_000, result)` / `demand_cu_between(9_000, 12_000, result)`
+//! - `demand_cu_within_pct_of(10_400, 5.0, result)` - tolerance around a recorded baseline
+//! - `demand_cu_at_most_at_index(1, 4_000, result)` - one top-level instruction, from its
+//!   `consumed X of Y compute units` log line
+//!
+//! ## Balance Changes
+//!
+//! [`balance_testing`] snapshots lamports and SPL token amounts before a transaction and
+//! asserts exact signed deltas afterwards, with the fee reported separately:
+//! - `let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[alice, bob]);`
+//! - `tracker.demand_deltas(&svm, &[BalanceDelta::Lamports(alice, -100), BalanceDelta::Lamports(bob, 100)])`
+//!
+//! ## Anchor Events
+//!
+//! With the `anchor` feature, events emitted by `emit!` (`Program data:` logs) and `emit_cpi!`
+//! (self-CPI inner instructions) can be decoded and asserted on:
+//! - `events::<MyEvent>(&result)` - every decoded `MyEvent`
+//! - `demand_event_emitted::<MyEvent>(|e| e.amount == 100, result)`
+//! - `demand_no_event::<MyEvent>(result)`
+//!
 //! ## Complete Examples
 //!
 //! **API Progression Tutorial**:
@@ -49,6 +235,18 @@
 //! - **Anchor**: [`examples/anchor/simple-anchor-tests/`](examples/anchor/simple-anchor-tests/) - Complete Anchor program testing with IDL integration
 //! - **Pinocchio**: [`examples/pinocchio/simple-pinocchio-tests/`](examples/pinocchio/simple-pinocchio-tests/) - Lightweight testing with minimal boilerplate
 
+pub mod account_builder;
+
+pub mod account_fixtures;
+
+pub mod account_testing;
+
+pub mod address_book;
+
+pub mod balance_testing;
+
+pub mod cpi_testing;
+
 #[cfg(feature = "anchor")]
 pub mod anchor_testing;
 
@@ -58,19 +256,45 @@ mod build_internal;
 #[cfg(feature = "cu_bench")]
 pub mod cu_bench;
 
+pub mod cu_testing;
+
+mod error;
+
+pub mod error_registry;
+
+pub mod log_parser;
+
+pub mod log_testing;
+
+pub mod report;
+
 #[cfg(feature = "pinocchio")]
 pub mod pinocchio_testing;
 
-// #[cfg(feature = "token")]
-// pub mod token_testing;
+pub mod return_data_testing;
+
+pub mod seed;
+
+pub mod snapshot_testing;
+
+pub mod test_context;
+
+#[cfg(feature = "token")]
+pub mod token_testing;
 
 // #[cfg(feature = "steel")]
 // pub mod steel_testing;
 
+pub use error::DemandError;
+
+use std::fmt;
+
 use num_traits::FromPrimitive;
 use solana_keypair::Keypair;
 use solana_signer::Signer;
-use solana_system_interface::error::SystemError;
+use solana_system_interface::{error::SystemError, program as system_program};
+use spl_associated_token_account::error::AssociatedTokenAccountError;
+use spl_token::error::TokenError;
 
 /// Convenient re-exports for LiteSVM testing.
 ///
@@ -92,7 +316,31 @@ use solana_system_interface::error::SystemError;
 /// - `demand_transaction_error` - Assert transaction-level errors  
 /// - `demand_instruction_error` - Assert instruction-level errors
 /// - `demand_system_error` - Assert system program errors (type-safe)
+/// - `demand_token_error` / `demand_associated_token_error` - Assert SPL Token and ATA errors (type-safe)
+/// - `demand_program_error` - Assert a program's own error enum variants (type-safe)
+/// - `demand_anchor_error` - Assert Anchor errors (`anchor` feature)
+/// - `demand_event_emitted` / `demand_no_event` - Assert Anchor events (`anchor` feature)
+/// - `check_*` - Non-panicking variants returning `Result<(), DemandError>`
+/// - `decode_error` / `register_program_errors` - Name custom program errors by the program that returned them
+/// - `demand_account_*` / `demand_lamports` / `demand_owner` / `demand_data_*` - Assert account state
+/// - `AccountBuilder` - Write accounts into the runtime without transactions
+/// - `load_account_fixture` / `export_account_fixture` - Replay and record `solana account --output json` dumps
+/// - `BalanceTracker` / `BalanceDelta` - Assert signed balance changes across a transaction
+/// - `parse_logs` / `failed_program` - Program logs as a tree of `ProgramInvocation`s, and the program that failed
+/// - `demand_program_logged` / `demand_logged_at_depth` / `demand_instruction_logged` - Scoped log assertions
+/// - `demand_logs_match` / `demand_logs_in_order` / `demand_logs_not_contain` / `demand_log_count` - Pattern log assertions
+/// - `demand_cu_at_most` / `demand_cu_between` / `demand_cu_within_pct_of` - Guard compute units
+/// - `demand_cpi_invoked` / `demand_cpi_count` / `demand_cpi_sequence` / `demand_cpi` - Assert CPIs
+/// - `demand_return_data` / `demand_return_data_decodes` - Assert `set_return_data` output
+/// - `demand_logs_snapshot` / `LogSnapshot` - Compare normalized logs to a checked-in snapshot
+/// - `describe` / `TransactionReport` - Render a transaction result for failure output
+/// - `AddressBook` / `address_name` - Label pubkeys in assertions, reports, snapshots and benchmarks
+/// - `TestContext` - LiteSVM with a funded payer, named actors and one-call `send`
+/// - `seeded_keypair` / `test_seed` / `SeedGuard` - Keypairs that are the same on every run
+/// - `demand_token_balance` / `demand_token_owner` / `demand_delegate` / `demand_frozen` / `demand_mint_supply` - Assert token state (`token` feature)
+/// - `demand_transfer_fee` / `demand_withheld_fees` / `demand_transfer_hook` / `demand_has_extension` - Assert Token-2022 extension state (`token` feature)
 /// - `DemandFluency` - Trait for fluent method chaining
+/// - `DemandChaining` - Trait for chaining several assertions on one result
 pub mod prelude {
     pub use litesvm;
     pub use solana_compute_budget_interface;
@@ -111,28 +359,188 @@ pub mod prelude {
     pub use solana_signer::Signer;
     pub use solana_system_interface::program as system_program;
 
+    pub use super::account_builder::AccountBuilder;
+
+    pub use super::account_fixtures::{
+        export_account_fixture, //
+        export_account_fixtures,
+        load_account_fixture,
+        load_account_fixture_dir,
+        load_account_fixtures,
+        AccountFixture,
+    };
+
+    pub use super::account_testing::{
+        demand_account_closed, //
+        demand_account_exists,
+        demand_data_eq,
+        demand_data_len,
+        demand_data_range_eq,
+        demand_executable,
+        demand_lamports,
+        demand_owner,
+    };
+
+    pub use super::address_book::{address_name, AddressBook};
+
+    pub use super::balance_testing::{BalanceDelta, BalanceTracker};
+
+    pub use super::cpi_testing::{
+        check_cpi, //
+        check_cpi_count,
+        check_cpi_invoked,
+        check_cpi_sequence,
+        cpis,
+        demand_cpi,
+        demand_cpi_count,
+        demand_cpi_invoked,
+        demand_cpi_sequence,
+        Cpi,
+        CpiMatcher,
+    };
+
+    pub use super::error_registry::{
+        decode_error, //
+        error_name,
+        register_error_decoder,
+        register_program_errors,
+        DecodedError,
+    };
+
+    pub use super::log_parser::{failed_program, parse_logs, InvocationStatus, ProgramInvocation};
+
+    pub use super::log_testing::{
+        check_instruction_logged, //
+        check_log_count,
+        check_logged_at_depth,
+        check_logs_in_order,
+        check_logs_match,
+        check_logs_not_contain,
+        check_program_logged,
+        demand_instruction_logged,
+        demand_log_count,
+        demand_logged_at_depth,
+        demand_logs_in_order,
+        demand_logs_match,
+        demand_logs_not_contain,
+        demand_program_logged,
+    };
+
+    pub use super::report::{describe, TransactionReport};
+
+    pub use super::return_data_testing::{
+        check_return_data, //
+        check_return_data_decodes,
+        decode_return_data,
+        demand_return_data,
+        demand_return_data_decodes,
+        return_data,
+    };
+
+    pub use super::snapshot_testing::{
+        check_logs_snapshot, //
+        demand_logs_snapshot,
+        LogSnapshot,
+    };
+
+    pub use super::seed::{seeded_keypair, test_seed, SeedGuard};
+
+    pub use super::test_context::TestContext;
+
+    pub use super::cu_testing::{
+        check_cu_at_most, //
+        check_cu_at_most_at_index,
+        check_cu_between,
+        check_cu_within_pct_of,
+        demand_cu_at_most,
+        demand_cu_at_most_at_index,
+        demand_cu_between,
+        demand_cu_within_pct_of,
+    };
+
     pub use super::{
-        demand_instruction_error, //
+        check_associated_token_error, //
+        check_associated_token_error_at_index,
+        check_instruction_error,
+        check_instruction_error_at_index,
+        check_logs_contain,
+        check_logs_contain_at_index,
+        check_program_error,
+        check_program_error_at_index,
+        check_system_error,
+        check_system_error_at_index,
+        check_token_error,
+        check_token_error_at_index,
+        check_transaction_error,
+        demand_associated_token_error,
+        demand_associated_token_error_at_index,
+        demand_instruction_error,
         demand_instruction_error_at_index,
         demand_logs_contain,
         demand_logs_contain_at_index,
+        demand_program_error,
+        demand_program_error_at_index,
         demand_system_error,
         demand_system_error_at_index,
+        demand_token_error,
+        demand_token_error_at_index,
         demand_transaction_error,
+        DemandChaining,
+        DemandError,
         DemandFluency,
     };
+
+    #[cfg(feature = "anchor")]
+    pub use super::anchor_testing::{
+        check_anchor_error, //
+        check_anchor_error_at_index,
+        check_event_emitted,
+        check_no_event,
+        demand_anchor_error,
+        demand_anchor_error_at_index,
+        demand_event_emitted,
+        demand_no_event,
+        events,
+        return_value,
+        simulate_view,
+    };
+
+    #[cfg(feature = "token")]
+    pub use super::token_testing::{
+        check_delegate, //
+        check_frozen,
+        check_has_extension,
+        check_interest_rate,
+        check_metadata_pointer,
+        check_mint_supply,
+        check_token_balance,
+        check_token_owner,
+        check_transfer_fee,
+        check_transfer_hook,
+        check_withheld_fees,
+        demand_delegate,
+        demand_frozen,
+        demand_has_extension,
+        demand_interest_rate,
+        demand_metadata_pointer,
+        demand_mint_supply,
+        demand_token_balance,
+        demand_token_owner,
+        demand_transfer_fee,
+        demand_transfer_hook,
+        demand_withheld_fees,
+    };
 }
 
 // "demanding solana"
 // - transaction errors
 // - instruction errors
 // - custom errors (the special case instruction error)
-// - anchor errors
-// - anchor events
-// - cu limits, etc, etc, etc
+// - cu limits (see cu_testing), etc, etc, etc
 
 use litesvm::{types::TransactionResult, LiteSVM};
 use solana_instruction::error::InstructionError;
+use solana_pubkey::Pubkey;
 use solana_transaction_error::TransactionError;
 
 /// Trait for fluent assertions on transaction results.
@@ -143,19 +551,134 @@ use solana_transaction_error::TransactionError;
 ///
 /// See the working examples in the repository for complete usage patterns.
 pub trait DemandFluency<T> {
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error<E: Into<anchor_lang::error::Error>>(self, expected_error: E);
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_at_index<E: Into<anchor_lang::error::Error>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    );
+    #[cfg(feature = "anchor")]
+    fn demand_event_emitted<E: anchor_lang::Event>(self, predicate: impl Fn(&E) -> bool);
+    #[cfg(feature = "anchor")]
+    fn demand_no_event<E: anchor_lang::Event>(self);
+    fn demand_associated_token_error(self, expected_error: AssociatedTokenAccountError);
+    fn demand_associated_token_error_at_index(
+        self,
+        expected_index: u8,
+        expected_error: AssociatedTokenAccountError,
+    );
+    fn demand_cpi(self, matcher: &cpi_testing::CpiMatcher);
+    fn demand_cpi_count(self, expected_index: u8, count: usize);
+    fn demand_cpi_invoked(self, program_id: &Pubkey);
+    fn demand_cpi_sequence(self, expected: &[Pubkey]);
+    fn demand_cu_at_most(self, limit: u64);
+    fn demand_cu_at_most_at_index(self, expected_index: u8, limit: u64);
+    fn demand_cu_between(self, lo: u64, hi: u64);
+    fn demand_cu_within_pct_of(self, baseline: u64, pct: f64);
     fn demand_instruction_error(self, expected_error: InstructionError);
     fn demand_instruction_error_at_index(
         self,
         expected_index: u8,
         expected_error: InstructionError,
     );
+    fn demand_instruction_logged(self, expected_index: u8, expected: &str);
+    fn demand_log_count(self, expected: &str, count: usize);
+    fn demand_logged_at_depth(self, program_id: &Pubkey, depth: usize, expected: &str);
     fn demand_logs_contain(self, expected: &str);
+    fn demand_logs_in_order(self, expected: &[&str]);
+    fn demand_logs_match(self, pattern: &str);
+    fn demand_logs_not_contain(self, unexpected: &str);
+    fn demand_logs_snapshot(self, name: &str);
+    fn demand_program_error<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_error: E,
+    );
+    fn demand_program_error_at_index<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    );
+    fn demand_program_logged(self, program_id: &Pubkey, expected: &str);
+    fn demand_return_data(self, program_id: &Pubkey, expected: &[u8]);
+    fn demand_return_data_decodes<D: borsh::BorshDeserialize>(self, predicate: impl Fn(&D) -> bool);
     fn demand_system_error(self, expected_error: SystemError);
     fn demand_system_error_at_index(self, expected_index: u8, expected_error: SystemError);
+    fn demand_token_error(self, expected_error: TokenError);
+    fn demand_token_error_at_index(self, expected_index: u8, expected_error: TokenError);
     fn demand_transaction_error(self, expected_error: TransactionError);
 }
 
 impl DemandFluency<TransactionResult> for TransactionResult {
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error<E: Into<anchor_lang::error::Error>>(self, expected_error: E) {
+        anchor_testing::demand_anchor_error(expected_error, self);
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_at_index<E: Into<anchor_lang::error::Error>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) {
+        anchor_testing::demand_anchor_error_at_index(expected_index, expected_error, self);
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_event_emitted<E: anchor_lang::Event>(self, predicate: impl Fn(&E) -> bool) {
+        anchor_testing::demand_event_emitted(predicate, self);
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_no_event<E: anchor_lang::Event>(self) {
+        anchor_testing::demand_no_event::<E>(self);
+    }
+
+    fn demand_associated_token_error(self, expected_error: AssociatedTokenAccountError) {
+        demand_associated_token_error(expected_error, self);
+    }
+
+    fn demand_associated_token_error_at_index(
+        self,
+        expected_index: u8,
+        expected_error: AssociatedTokenAccountError,
+    ) {
+        demand_associated_token_error_at_index(expected_index, expected_error, self);
+    }
+
+    fn demand_cpi(self, matcher: &cpi_testing::CpiMatcher) {
+        cpi_testing::demand_cpi(matcher, self);
+    }
+
+    fn demand_cpi_count(self, expected_index: u8, count: usize) {
+        cpi_testing::demand_cpi_count(expected_index, count, self);
+    }
+
+    fn demand_cpi_invoked(self, program_id: &Pubkey) {
+        cpi_testing::demand_cpi_invoked(program_id, self);
+    }
+
+    fn demand_cpi_sequence(self, expected: &[Pubkey]) {
+        cpi_testing::demand_cpi_sequence(expected, self);
+    }
+
+    fn demand_cu_at_most(self, limit: u64) {
+        cu_testing::demand_cu_at_most(limit, self);
+    }
+
+    fn demand_cu_at_most_at_index(self, expected_index: u8, limit: u64) {
+        cu_testing::demand_cu_at_most_at_index(expected_index, limit, self);
+    }
+
+    fn demand_cu_between(self, lo: u64, hi: u64) {
+        cu_testing::demand_cu_between(lo, hi, self);
+    }
+
+    fn demand_cu_within_pct_of(self, baseline: u64, pct: f64) {
+        cu_testing::demand_cu_within_pct_of(baseline, pct, self);
+    }
+
     fn demand_instruction_error(self, expected_error: InstructionError) {
         demand_instruction_error(expected_error, self);
     }
@@ -168,29 +691,453 @@ impl DemandFluency<TransactionResult> for TransactionResult {
         demand_instruction_error_at_index(expected_index, expected_error, self);
     }
 
+    fn demand_instruction_logged(self, expected_index: u8, expected: &str) {
+        log_testing::demand_instruction_logged(expected_index, expected, self);
+    }
+
+    fn demand_log_count(self, expected: &str, count: usize) {
+        log_testing::demand_log_count(expected, count, self);
+    }
+
+    fn demand_logged_at_depth(self, program_id: &Pubkey, depth: usize, expected: &str) {
+        log_testing::demand_logged_at_depth(program_id, depth, expected, self);
+    }
+
     fn demand_logs_contain(self, expected: &str) {
         demand_logs_contain(expected, self);
     }
 
-    fn demand_system_error(self, expected_error: SystemError) {
-        demand_system_error(expected_error, self);
+    fn demand_logs_in_order(self, expected: &[&str]) {
+        log_testing::demand_logs_in_order(expected, self);
+    }
+
+    fn demand_logs_match(self, pattern: &str) {
+        log_testing::demand_logs_match(pattern, self);
+    }
+
+    fn demand_logs_not_contain(self, unexpected: &str) {
+        log_testing::demand_logs_not_contain(unexpected, self);
+    }
+
+    fn demand_logs_snapshot(self, name: &str) {
+        snapshot_testing::demand_logs_snapshot(name, self);
+    }
+
+    fn demand_program_error<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_error: E,
+    ) {
+        demand_program_error(expected_error, self);
+    }
+
+    fn demand_program_error_at_index<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) {
+        demand_program_error_at_index(expected_index, expected_error, self);
+    }
+
+    fn demand_program_logged(self, program_id: &Pubkey, expected: &str) {
+        log_testing::demand_program_logged(program_id, expected, self);
+    }
+
+    fn demand_return_data(self, program_id: &Pubkey, expected: &[u8]) {
+        return_data_testing::demand_return_data(program_id, expected, self);
+    }
+
+    fn demand_return_data_decodes<D: borsh::BorshDeserialize>(
+        self,
+        predicate: impl Fn(&D) -> bool,
+    ) {
+        return_data_testing::demand_return_data_decodes(predicate, self);
+    }
+
+    fn demand_system_error(self, expected_error: SystemError) {
+        demand_system_error(expected_error, self);
+    }
+
+    fn demand_system_error_at_index(self, expected_index: u8, expected_error: SystemError) {
+        demand_system_error_at_index(expected_index, expected_error, self);
+    }
+
+    fn demand_token_error(self, expected_error: TokenError) {
+        demand_token_error(expected_error, self);
+    }
+
+    fn demand_token_error_at_index(self, expected_index: u8, expected_error: TokenError) {
+        demand_token_error_at_index(expected_index, expected_error, self);
+    }
+
+    fn demand_transaction_error(self, expected_error: TransactionError) {
+        demand_transaction_error(expected_error, self);
+    }
+}
+
+/// Trait for chainable assertions on transaction results.
+///
+/// Where [`DemandFluency`] consumes the [`TransactionResult`] on every call, each `_and`
+/// method here returns the result so that several assertions can be made against the same
+/// transaction. End the chain with [`accept`](DemandChaining::accept).
+///
+/// ```text
+/// svm.send_transaction(tx)
+///     .demand_system_error_at_index_and(1, SystemError::ResultWithNegativeLamports)
+///     .demand_logs_contain_and("insufficient lamports")
+///     .accept();
+/// ```
+pub trait DemandChaining<T> {
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_and<E: Into<anchor_lang::error::Error>>(self, expected_error: E)
+        -> Self;
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_at_index_and<E: Into<anchor_lang::error::Error>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) -> Self;
+    #[cfg(feature = "anchor")]
+    fn demand_event_emitted_and<E: anchor_lang::Event>(
+        self,
+        predicate: impl Fn(&E) -> bool,
+    ) -> Self;
+    #[cfg(feature = "anchor")]
+    fn demand_no_event_and<E: anchor_lang::Event>(self) -> Self;
+    fn demand_associated_token_error_and(self, expected_error: AssociatedTokenAccountError)
+        -> Self;
+    fn demand_associated_token_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: AssociatedTokenAccountError,
+    ) -> Self;
+    fn demand_cpi_and(self, matcher: &cpi_testing::CpiMatcher) -> Self;
+    fn demand_cpi_count_and(self, expected_index: u8, count: usize) -> Self;
+    fn demand_cpi_invoked_and(self, program_id: &Pubkey) -> Self;
+    fn demand_cpi_sequence_and(self, expected: &[Pubkey]) -> Self;
+    fn demand_cu_at_most_and(self, limit: u64) -> Self;
+    fn demand_cu_at_most_at_index_and(self, expected_index: u8, limit: u64) -> Self;
+    fn demand_cu_between_and(self, lo: u64, hi: u64) -> Self;
+    fn demand_cu_within_pct_of_and(self, baseline: u64, pct: f64) -> Self;
+    fn demand_instruction_error_and(self, expected_error: InstructionError) -> Self;
+    fn demand_instruction_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: InstructionError,
+    ) -> Self;
+    fn demand_instruction_logged_and(self, expected_index: u8, expected: &str) -> Self;
+    fn demand_log_count_and(self, expected: &str, count: usize) -> Self;
+    fn demand_logged_at_depth_and(self, program_id: &Pubkey, depth: usize, expected: &str) -> Self;
+    fn demand_logs_contain_and(self, expected: &str) -> Self;
+    fn demand_logs_contain_at_index_and(self, expected: &str, expected_index: usize) -> Self;
+    fn demand_logs_in_order_and(self, expected: &[&str]) -> Self;
+    fn demand_logs_match_and(self, pattern: &str) -> Self;
+    fn demand_logs_not_contain_and(self, unexpected: &str) -> Self;
+    fn demand_logs_snapshot_and(self, name: &str) -> Self;
+    fn demand_program_error_and<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_error: E,
+    ) -> Self;
+    fn demand_program_error_at_index_and<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) -> Self;
+    fn demand_program_logged_and(self, program_id: &Pubkey, expected: &str) -> Self;
+    fn demand_return_data_and(self, program_id: &Pubkey, expected: &[u8]) -> Self;
+    fn demand_return_data_decodes_and<D: borsh::BorshDeserialize>(
+        self,
+        predicate: impl Fn(&D) -> bool,
+    ) -> Self;
+    fn demand_system_error_and(self, expected_error: SystemError) -> Self;
+    fn demand_system_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: SystemError,
+    ) -> Self;
+    fn demand_token_error_and(self, expected_error: TokenError) -> Self;
+    fn demand_token_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: TokenError,
+    ) -> Self;
+    fn demand_transaction_error_and(self, expected_error: TransactionError) -> Self;
+
+    /// Terminal method that consumes the result once all assertions have been made.
+    fn accept(self);
+}
+
+impl DemandChaining<TransactionResult> for TransactionResult {
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_and<E: Into<anchor_lang::error::Error>>(
+        self,
+        expected_error: E,
+    ) -> Self {
+        or_panic_with(
+            anchor_testing::check_anchor_error(expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_anchor_error_at_index_and<E: Into<anchor_lang::error::Error>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) -> Self {
+        or_panic_with(
+            anchor_testing::check_anchor_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_event_emitted_and<E: anchor_lang::Event>(
+        self,
+        predicate: impl Fn(&E) -> bool,
+    ) -> Self {
+        or_panic_with(anchor_testing::check_event_emitted(predicate, &self), &self);
+        self
+    }
+
+    #[cfg(feature = "anchor")]
+    fn demand_no_event_and<E: anchor_lang::Event>(self) -> Self {
+        or_panic_with(anchor_testing::check_no_event::<E>(&self), &self);
+        self
+    }
+
+    fn demand_associated_token_error_and(
+        self,
+        expected_error: AssociatedTokenAccountError,
+    ) -> Self {
+        or_panic_with(check_associated_token_error(expected_error, &self), &self);
+        self
+    }
+
+    fn demand_associated_token_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: AssociatedTokenAccountError,
+    ) -> Self {
+        or_panic_with(
+            check_associated_token_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_cpi_and(self, matcher: &cpi_testing::CpiMatcher) -> Self {
+        or_panic_with(cpi_testing::check_cpi(matcher, &self), &self);
+        self
+    }
+
+    fn demand_cpi_count_and(self, expected_index: u8, count: usize) -> Self {
+        or_panic_with(
+            cpi_testing::check_cpi_count(expected_index, count, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_cpi_invoked_and(self, program_id: &Pubkey) -> Self {
+        or_panic_with(cpi_testing::check_cpi_invoked(program_id, &self), &self);
+        self
+    }
+
+    fn demand_cpi_sequence_and(self, expected: &[Pubkey]) -> Self {
+        or_panic_with(cpi_testing::check_cpi_sequence(expected, &self), &self);
+        self
+    }
+
+    fn demand_cu_at_most_and(self, limit: u64) -> Self {
+        or_panic_with(cu_testing::check_cu_at_most(limit, &self), &self);
+        self
+    }
+
+    fn demand_cu_at_most_at_index_and(self, expected_index: u8, limit: u64) -> Self {
+        or_panic_with(
+            cu_testing::check_cu_at_most_at_index(expected_index, limit, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_cu_between_and(self, lo: u64, hi: u64) -> Self {
+        or_panic_with(cu_testing::check_cu_between(lo, hi, &self), &self);
+        self
+    }
+
+    fn demand_cu_within_pct_of_and(self, baseline: u64, pct: f64) -> Self {
+        or_panic_with(
+            cu_testing::check_cu_within_pct_of(baseline, pct, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_instruction_error_and(self, expected_error: InstructionError) -> Self {
+        or_panic_with(check_instruction_error(expected_error, &self), &self);
+        self
+    }
+
+    fn demand_instruction_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: InstructionError,
+    ) -> Self {
+        or_panic_with(
+            check_instruction_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_instruction_logged_and(self, expected_index: u8, expected: &str) -> Self {
+        or_panic_with(
+            log_testing::check_instruction_logged(expected_index, expected, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_log_count_and(self, expected: &str, count: usize) -> Self {
+        or_panic_with(log_testing::check_log_count(expected, count, &self), &self);
+        self
+    }
+
+    fn demand_logged_at_depth_and(self, program_id: &Pubkey, depth: usize, expected: &str) -> Self {
+        or_panic_with(
+            log_testing::check_logged_at_depth(program_id, depth, expected, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_logs_contain_and(self, expected: &str) -> Self {
+        or_panic_with(check_logs_contain(expected, &self), &self);
+        self
+    }
+
+    fn demand_logs_contain_at_index_and(self, expected: &str, expected_index: usize) -> Self {
+        or_panic_with(
+            check_logs_contain_at_index(expected, expected_index, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_logs_in_order_and(self, expected: &[&str]) -> Self {
+        or_panic_with(log_testing::check_logs_in_order(expected, &self), &self);
+        self
+    }
+
+    fn demand_logs_match_and(self, pattern: &str) -> Self {
+        or_panic_with(log_testing::check_logs_match(pattern, &self), &self);
+        self
+    }
+
+    fn demand_logs_not_contain_and(self, unexpected: &str) -> Self {
+        or_panic_with(
+            log_testing::check_logs_not_contain(unexpected, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_logs_snapshot_and(self, name: &str) -> Self {
+        or_panic_with(snapshot_testing::check_logs_snapshot(name, &self), &self);
+        self
+    }
+
+    fn demand_program_error_and<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_error: E,
+    ) -> Self {
+        or_panic_with(check_program_error(expected_error, &self), &self);
+        self
+    }
+
+    fn demand_program_error_at_index_and<E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>>(
+        self,
+        expected_index: u8,
+        expected_error: E,
+    ) -> Self {
+        or_panic_with(
+            check_program_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_program_logged_and(self, program_id: &Pubkey, expected: &str) -> Self {
+        or_panic_with(
+            log_testing::check_program_logged(program_id, expected, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_return_data_and(self, program_id: &Pubkey, expected: &[u8]) -> Self {
+        or_panic_with(
+            return_data_testing::check_return_data(program_id, expected, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_return_data_decodes_and<D: borsh::BorshDeserialize>(
+        self,
+        predicate: impl Fn(&D) -> bool,
+    ) -> Self {
+        or_panic_with(
+            return_data_testing::check_return_data_decodes(predicate, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_system_error_and(self, expected_error: SystemError) -> Self {
+        or_panic_with(check_system_error(expected_error, &self), &self);
+        self
+    }
+
+    fn demand_system_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: SystemError,
+    ) -> Self {
+        or_panic_with(
+            check_system_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
+    }
+
+    fn demand_token_error_and(self, expected_error: TokenError) -> Self {
+        or_panic_with(check_token_error(expected_error, &self), &self);
+        self
     }
 
-    fn demand_system_error_at_index(self, expected_index: u8, expected_error: SystemError) {
-        demand_system_error_at_index(expected_index, expected_error, self);
+    fn demand_token_error_at_index_and(
+        self,
+        expected_index: u8,
+        expected_error: TokenError,
+    ) -> Self {
+        or_panic_with(
+            check_token_error_at_index(expected_index, expected_error, &self),
+            &self,
+        );
+        self
     }
 
-    fn demand_transaction_error(self, expected_error: TransactionError) {
-        demand_transaction_error(expected_error, self);
+    fn demand_transaction_error_and(self, expected_error: TransactionError) -> Self {
+        or_panic_with(check_transaction_error(expected_error, &self), &self);
+        self
     }
-}
 
-// FUTURE IDEA: support for chaining methods on the result:
-// pub trait DemandChaining<T> {
-//     fn demand_logs_contain_and(self, expected: &str) -> Self;
-//     fn demand_system_error_and(self, expected_error: SystemError) -> Self;
-//     fn accept(self); // terminal method to consume the result
-// }
+    fn accept(self) {}
+}
 
 /// Asserts that a transaction's logs contain a specific string.
 ///
@@ -246,25 +1193,28 @@ impl DemandFluency<TransactionResult> for TransactionResult {
 /// This function works with both successful and failed transactions. For failed transactions,
 /// it searches through the logs in the error metadata.
 pub fn demand_logs_contain(expected: &str, result: TransactionResult) {
-    let logs = match &result {
-        Ok(meta) => &meta.logs,
-        Err(meta) => &meta.meta.logs,
-    };
+    or_panic_with(check_logs_contain(expected, &result), &result);
+}
+
+/// Checks that a transaction's logs contain a specific string, without panicking.
+///
+/// Non-panicking version of [`demand_logs_contain`].
+///
+/// # Errors
+///
+/// Returns [`DemandError::LogNotFound`] carrying every log entry when `expected` is not found.
+pub fn check_logs_contain(expected: &str, result: &TransactionResult) -> Result<(), DemandError> {
+    let logs = logs_of(result);
 
     if logs.iter().any(|log| log.contains(expected)) {
-        return;
+        return Ok(());
     }
 
-    panic!(
-        "Expected {:?} among {} log entries: {}",
-        expected,
-        logs.len(),
-        logs.iter()
-            .enumerate()
-            .map(|(i, log)| format!("[{}]: {}", i, log))
-            .collect::<Vec<_>>()
-            .join(", ")
-    );
+    Err(DemandError::LogNotFound {
+        expected: expected.to_string(),
+        index: None,
+        logs: logs.clone(),
+    })
 }
 
 /// Asserts that a specific log entry contains an expected string.
@@ -297,24 +1247,34 @@ pub fn demand_logs_contain_at_index(
     expected_index: usize,
     result: TransactionResult,
 ) {
-    let logs = match &result {
-        Ok(meta) => &meta.logs,
-        Err(meta) => &meta.meta.logs,
-    };
+    or_panic_with(
+        check_logs_contain_at_index(expected, expected_index, &result),
+        &result,
+    );
+}
 
-    let Some(log_entry) = logs.get(expected_index) else {
-        panic!(
-            "Log index {} out of bounds, only {} entries available",
-            expected_index,
-            logs.len()
-        );
-    };
+/// Checks that a specific log entry contains an expected string, without panicking.
+///
+/// Non-panicking version of [`demand_logs_contain_at_index`].
+///
+/// # Errors
+///
+/// Returns [`DemandError::LogNotFound`] with `index` set when the log index is out of bounds
+/// or the entry doesn't contain `expected`.
+pub fn check_logs_contain_at_index(
+    expected: &str,
+    expected_index: usize,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let logs = logs_of(result);
 
-    if !log_entry.contains(expected) {
-        panic!(
-            "Expected {:?} at log index {} but found: {:?}",
-            expected, expected_index, log_entry
-        );
+    match logs.get(expected_index) {
+        Some(log_entry) if log_entry.contains(expected) => Ok(()),
+        _ => Err(DemandError::LogNotFound {
+            expected: expected.to_string(),
+            index: Some(expected_index),
+            logs: logs.clone(),
+        }),
     }
 }
 
@@ -347,17 +1307,48 @@ pub fn demand_logs_contain_at_index(
 /// );
 /// ```
 pub fn demand_instruction_error(expected_error: InstructionError, result: TransactionResult) {
+    or_panic_with(check_instruction_error(expected_error, &result), &result);
+}
+
+/// Checks that a transaction fails with a specific instruction error, without panicking.
+///
+/// Non-panicking version of [`demand_instruction_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongError`] if the error is not the expected instruction error
+pub fn check_instruction_error(
+    expected_error: InstructionError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
     let Err(e) = result else {
-        panic!("Expected {} but transaction succeeded", expected_error);
+        return Err(DemandError::UnexpectedSuccess {
+            expected: expected_error.to_string(),
+            expected_index: None,
+        });
     };
 
-    let TransactionError::InstructionError(_, observed_error) = &e.err else {
-        panic!("Expected {} but got: {}", expected_error, e.err);
+    let TransactionError::InstructionError(observed_index, observed_error) = &e.err else {
+        return Err(DemandError::WrongError {
+            expected: expected_error.to_string(),
+            observed: e.err.to_string(),
+            index: None,
+        });
     };
 
     if *observed_error != expected_error {
-        panic!("Expected {} but got {}", expected_error, observed_error);
+        return Err(DemandError::WrongError {
+            expected: expected_error.to_string(),
+            observed: error_registry::decode_error(result).map_or_else(
+                || observed_error.to_string(),
+                |decoded| decoded.name_and_code(),
+            ),
+            index: Some(*observed_index),
+        });
     }
+
+    Ok(())
 }
 
 /// Asserts that a specific instruction fails with a specific error.
@@ -397,33 +1388,61 @@ pub fn demand_instruction_error_at_index(
     expected_error: InstructionError,
     result: TransactionResult,
 ) {
+    or_panic_with(
+        check_instruction_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that a specific instruction fails with a specific error, without panicking.
+///
+/// Non-panicking version of [`demand_instruction_error_at_index`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongError`] if the error is not an instruction error, or doesn't match
+/// - [`DemandError::WrongIndex`] if the error occurred at a different instruction index
+pub fn check_instruction_error_at_index(
+    expected_index: u8,
+    expected_error: InstructionError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
     let Err(e) = result else {
-        panic!(
-            "Expected {} at index {} but transaction succeeded",
-            expected_error, expected_index
-        );
+        return Err(DemandError::UnexpectedSuccess {
+            expected: expected_error.to_string(),
+            expected_index: Some(expected_index),
+        });
     };
 
     let TransactionError::InstructionError(observed_index, observed_error) = &e.err else {
-        panic!(
-            "Expected {} at index {} but got: {}",
-            expected_error, expected_index, e.err
-        );
+        return Err(DemandError::WrongError {
+            expected: expected_error.to_string(),
+            observed: e.err.to_string(),
+            index: None,
+        });
     };
 
     if *observed_index != expected_index {
-        panic!(
-            "Expected {} at index {} but got error at index {}",
-            expected_error, expected_index, observed_index
-        );
+        return Err(DemandError::WrongIndex {
+            expected: expected_error.to_string(),
+            expected_index,
+            observed_index: *observed_index,
+        });
     }
 
     if *observed_error != expected_error {
-        panic!(
-            "Expected {} at index {} but got {} at index {}",
-            expected_error, expected_index, observed_error, observed_index
-        );
+        return Err(DemandError::WrongError {
+            expected: expected_error.to_string(),
+            observed: error_registry::decode_error(result).map_or_else(
+                || observed_error.to_string(),
+                |decoded| decoded.name_and_code(),
+            ),
+            index: Some(*observed_index),
+        });
     }
+
+    Ok(())
 }
 
 /// Asserts that a transaction error matches the expected error.
@@ -450,13 +1469,41 @@ pub fn demand_instruction_error_at_index(
 /// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
 ///
 pub fn demand_transaction_error(expected: TransactionError, result: TransactionResult) {
+    or_panic_with(check_transaction_error(expected, &result), &result);
+}
+
+/// Checks that a transaction error matches the expected error, without panicking.
+///
+/// Non-panicking version of [`demand_transaction_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongError`] if the transaction failed with a different error
+pub fn check_transaction_error(
+    expected: TransactionError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
     let Err(e) = result else {
-        panic!("Expected {} but transaction succeeded", expected);
+        return Err(DemandError::UnexpectedSuccess {
+            expected: expected.to_string(),
+            expected_index: None,
+        });
     };
 
     if e.err != expected {
-        panic!("Expected {} but got {}", expected, e.err);
+        let (observed, index) = match error_registry::decode_error(result) {
+            Some(decoded) => (decoded.name_and_code(), Some(decoded.index)),
+            None => (e.err.to_string(), None),
+        };
+        return Err(DemandError::WrongError {
+            expected: expected.to_string(),
+            observed,
+            index,
+        });
     }
+
+    Ok(())
 }
 
 /// Asserts that a system error occurs, regardless of which instruction index produced it.
@@ -477,25 +1524,25 @@ pub fn demand_transaction_error(expected: TransactionError, result: TransactionR
 /// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
 ///
 pub fn demand_system_error(expected_error: SystemError, result: TransactionResult) {
-    let Err(e) = &result else {
-        panic!("Expected {} but transaction succeeded", expected_error);
-    };
-
-    let TransactionError::InstructionError(_, InstructionError::Custom(observed_code)) = &e.err
-    else {
-        panic!("Expected {} but got: {}", expected_error, e.err);
-    };
-
-    let Some(observed_error) = SystemError::from_u64(*observed_code as u64) else {
-        panic!(
-            "Expected {} but got invalid code {}",
-            expected_error, observed_code
-        );
-    };
+    or_panic_with(check_system_error(expected_error, &result), &result);
+}
 
-    if observed_error != expected_error {
-        panic!("Expected {} but got: {}", expected_error, observed_error);
-    }
+/// Checks that a system error occurs, regardless of instruction index, without panicking.
+///
+/// Non-panicking version of [`demand_system_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as a [`SystemError`], or decodes to a different one
+pub fn check_system_error(
+    expected_error: SystemError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(&system_program::ID, None, expected_error, result)
 }
 
 /// Asserts that a system error occurs at a specific instruction index.
@@ -515,41 +1562,523 @@ pub fn demand_system_error_at_index(
     expected_error: SystemError,
     result: TransactionResult,
 ) {
-    let Err(e) = &result else {
-        panic!(
-            "Expected {} at index {} but transaction succeeded",
-            expected_error, expected_index
-        );
+    or_panic_with(
+        check_system_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that a system error occurs at a specific instruction index, without panicking.
+///
+/// Non-panicking version of [`demand_system_error_at_index`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongIndex`] if the error occurred at a different instruction index
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as a [`SystemError`], or decodes to a different one
+pub fn check_system_error_at_index(
+    expected_index: u8,
+    expected_error: SystemError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(
+        &system_program::ID,
+        Some(expected_index),
+        expected_error,
+        result,
+    )
+}
+
+/// Asserts that SPL Token fails with a specific [`TokenError`], regardless of instruction index.
+///
+/// The error must come from SPL Token itself, whether invoked directly or via CPI: the same
+/// custom code from another program (say `0x1`, also the system program's
+/// `ResultWithNegativeLamports`) fails the assertion instead of decoding as `TokenError`.
+///
+/// # Arguments
+///
+/// * `expected_error` - The expected token error
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the transaction succeeded, failed with a non-custom error, the custom error came
+/// from another program, or it decodes to a different [`TokenError`].
+///
+/// # Example
+///
+/// ```text
+/// demand_token_error(TokenError::InsufficientFunds, result);
+/// ```
+pub fn demand_token_error(expected_error: TokenError, result: TransactionResult) {
+    or_panic_with(check_token_error(expected_error, &result), &result);
+}
+
+/// Checks that SPL Token fails with a specific [`TokenError`], without panicking.
+///
+/// Non-panicking version of [`demand_token_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as a [`TokenError`], or decodes to a different one
+pub fn check_token_error(
+    expected_error: TokenError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(&spl_token::ID, None, expected_error, result)
+}
+
+/// Asserts that SPL Token fails with a specific [`TokenError`] within a specific instruction.
+///
+/// `expected_index` is the top-level instruction that failed; SPL Token may have been
+/// invoked by it directly or via CPI.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction that should produce the error
+/// * `expected_error` - The expected token error
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Example
+///
+/// ```text
+/// demand_token_error_at_index(2, TokenError::OwnerMismatch, result);
+/// ```
+pub fn demand_token_error_at_index(
+    expected_index: u8,
+    expected_error: TokenError,
+    result: TransactionResult,
+) {
+    or_panic_with(
+        check_token_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that SPL Token fails with a specific [`TokenError`] within a specific instruction,
+/// without panicking.
+///
+/// Non-panicking version of [`demand_token_error_at_index`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongIndex`] if the error occurred at a different instruction index
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as a [`TokenError`], or decodes to a different one
+pub fn check_token_error_at_index(
+    expected_index: u8,
+    expected_error: TokenError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(&spl_token::ID, Some(expected_index), expected_error, result)
+}
+
+/// Asserts that the associated token account program fails with a specific
+/// [`AssociatedTokenAccountError`], regardless of instruction index.
+///
+/// Most ATA failures are SPL Token or system program errors raised by its CPIs; assert those
+/// with [`demand_token_error`] or [`demand_system_error`]. This only matches errors the ATA
+/// program raised itself.
+///
+/// # Arguments
+///
+/// * `expected_error` - The expected associated token account error
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Example
+///
+/// ```text
+/// demand_associated_token_error(AssociatedTokenAccountError::InvalidOwner, result);
+/// ```
+pub fn demand_associated_token_error(
+    expected_error: AssociatedTokenAccountError,
+    result: TransactionResult,
+) {
+    or_panic_with(
+        check_associated_token_error(expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that the associated token account program fails with a specific
+/// [`AssociatedTokenAccountError`], without panicking.
+///
+/// Non-panicking version of [`demand_associated_token_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as an [`AssociatedTokenAccountError`], or decodes to a different one
+pub fn check_associated_token_error(
+    expected_error: AssociatedTokenAccountError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(
+        &spl_associated_token_account::ID,
+        None,
+        expected_error,
+        result,
+    )
+}
+
+/// Asserts that the associated token account program fails with a specific
+/// [`AssociatedTokenAccountError`] within a specific instruction.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction that should produce the error
+/// * `expected_error` - The expected associated token account error
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Example
+///
+/// ```text
+/// demand_associated_token_error_at_index(1, AssociatedTokenAccountError::InvalidOwner, result);
+/// ```
+pub fn demand_associated_token_error_at_index(
+    expected_index: u8,
+    expected_error: AssociatedTokenAccountError,
+    result: TransactionResult,
+) {
+    or_panic_with(
+        check_associated_token_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that the associated token account program fails with a specific
+/// [`AssociatedTokenAccountError`] within a specific instruction, without panicking.
+///
+/// Non-panicking version of [`demand_associated_token_error_at_index`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongIndex`] if the error occurred at a different instruction index
+/// - [`DemandError::WrongProgram`] if the custom error came from another program
+/// - [`DemandError::UnknownProgram`] if the logs don't show which program raised it
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, doesn't
+///   decode as an [`AssociatedTokenAccountError`], or decodes to a different one
+pub fn check_associated_token_error_at_index(
+    expected_index: u8,
+    expected_error: AssociatedTokenAccountError,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    check_decoded_error(
+        &spl_associated_token_account::ID,
+        Some(expected_index),
+        expected_error,
+        result,
+    )
+}
+
+/// Checks a custom error against the error enum of the program that is expected to raise it.
+///
+/// Custom codes are only meaningful to the program that returned them, so the failing
+/// program is identified from the logs first (see [`log_parser::failed_program`]). If the
+/// logs can't tell, the code isn't decoded at all.
+fn check_decoded_error<E>(
+    program_id: &Pubkey,
+    expected_index: Option<u8>,
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError>
+where
+    E: FromPrimitive + fmt::Display + PartialEq,
+{
+    let expected = expected_error.to_string();
+
+    let Err(e) = result else {
+        return Err(DemandError::UnexpectedSuccess {
+            expected,
+            expected_index,
+        });
     };
 
     let TransactionError::InstructionError(observed_index, InstructionError::Custom(observed_code)) =
         &e.err
     else {
-        panic!(
-            "Expected {} at index {} but got: {:?}",
-            expected_error, expected_index, e.err
-        );
+        return Err(DemandError::WrongError {
+            expected,
+            observed: e.err.to_string(),
+            index: None,
+        });
     };
 
-    if *observed_index != expected_index {
-        panic!(
-            "Expected {} at index {} but got error at index {}",
-            expected_error, expected_index, observed_index
-        );
+    if let Some(expected_index) = expected_index {
+        if *observed_index != expected_index {
+            return Err(DemandError::WrongIndex {
+                expected,
+                expected_index,
+                observed_index: *observed_index,
+            });
+        }
     }
 
-    let Some(observed_error) = SystemError::from_u64(*observed_code as u64) else {
-        panic!(
-            "Expected {} at index {} but got invalid code {} at index {}",
-            expected_error, expected_index, observed_code, observed_index
-        );
+    let Some(observed_program) = log_parser::failed_program(result) else {
+        return Err(DemandError::UnknownProgram {
+            expected,
+            expected_program: *program_id,
+            code: *observed_code,
+            index: *observed_index,
+        });
     };
+    if observed_program != *program_id {
+        return Err(DemandError::WrongProgram {
+            expected,
+            expected_program: *program_id,
+            observed_program,
+            code: *observed_code,
+            index: *observed_index,
+        });
+    }
 
-    if observed_error != expected_error {
-        panic!(
-            "Expected {} at index {} but got {} at index {}",
-            expected_error, expected_index, observed_error, observed_index
-        );
+    let decoded = E::from_u32(*observed_code);
+    if decoded.as_ref() == Some(&expected_error) {
+        return Ok(());
+    }
+
+    let observed = registered_error_name(result).unwrap_or_else(|| match decoded {
+        Some(observed_error) => observed_error.to_string(),
+        None => format!("invalid code {}", observed_code),
+    });
+
+    Err(DemandError::WrongError {
+        expected,
+        observed,
+        index: expected_index.map(|_| *observed_index),
+    })
+}
+
+/// Asserts that a program's own error enum variant occurs, regardless of instruction index.
+///
+/// Works with any error enum that maps to and from the `u32` carried by
+/// [`InstructionError::Custom`], as Pinocchio and native programs typically define with
+/// `num_derive::FromPrimitive` and a `From<MyError> for u32` (or `ProgramError`) impl.
+///
+/// # Arguments
+///
+/// * `expected_error` - The expected variant of the program's error enum
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the transaction succeeded, or failed with a different error. The observed
+/// custom code is shown as the variant it decodes to, or as `unknown code N for E`.
+///
+/// # Example
+///
+/// ```text
+/// demand_program_error(VaultError::InsufficientFunds, result);
+/// ```
+pub fn demand_program_error<E>(expected_error: E, result: TransactionResult)
+where
+    E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>,
+{
+    or_panic_with(check_program_error(expected_error, &result), &result);
+}
+
+/// Checks that a program's own error enum variant occurs, without panicking.
+///
+/// Non-panicking version of [`demand_program_error`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, or its
+///   code is a different variant of `E` (or none at all)
+pub fn check_program_error<E>(
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError>
+where
+    E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>,
+{
+    check_custom_error(None, expected_error, result)
+}
+
+/// Asserts that a program's own error enum variant occurs at a specific instruction index.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction that should produce the error
+/// * `expected_error` - The expected variant of the program's error enum
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Example
+///
+/// ```text
+/// demand_program_error_at_index(1, VaultError::InsufficientFunds, result);
+/// ```
+pub fn demand_program_error_at_index<E>(
+    expected_index: u8,
+    expected_error: E,
+    result: TransactionResult,
+) where
+    E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>,
+{
+    or_panic_with(
+        check_program_error_at_index(expected_index, expected_error, &result),
+        &result,
+    );
+}
+
+/// Checks that a program's own error enum variant occurs at a specific instruction index,
+/// without panicking.
+///
+/// Non-panicking version of [`demand_program_error_at_index`].
+///
+/// # Errors
+///
+/// - [`DemandError::UnexpectedSuccess`] if the transaction succeeded
+/// - [`DemandError::WrongIndex`] if the error occurred at a different instruction index
+/// - [`DemandError::WrongError`] if the error is not a custom instruction error, or its
+///   code is a different variant of `E` (or none at all)
+pub fn check_program_error_at_index<E>(
+    expected_index: u8,
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError>
+where
+    E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>,
+{
+    check_custom_error(Some(expected_index), expected_error, result)
+}
+
+fn check_custom_error<E>(
+    expected_index: Option<u8>,
+    expected_error: E,
+    result: &TransactionResult,
+) -> Result<(), DemandError>
+where
+    E: FromPrimitive + fmt::Debug + PartialEq + Into<u32>,
+{
+    let expected = format!("{:?}", expected_error);
+
+    let Err(e) = result else {
+        return Err(DemandError::UnexpectedSuccess {
+            expected,
+            expected_index,
+        });
+    };
+
+    let TransactionError::InstructionError(observed_index, InstructionError::Custom(observed_code)) =
+        &e.err
+    else {
+        return Err(DemandError::WrongError {
+            expected,
+            observed: e.err.to_string(),
+            index: None,
+        });
+    };
+
+    if let Some(expected_index) = expected_index {
+        if *observed_index != expected_index {
+            return Err(DemandError::WrongIndex {
+                expected,
+                expected_index,
+                observed_index: *observed_index,
+            });
+        }
+    }
+
+    if *observed_code == expected_error.into() {
+        return Ok(());
+    }
+
+    let observed =
+        registered_error_name(result).unwrap_or_else(|| match E::from_u32(*observed_code) {
+            Some(observed_error) => format!("{:?}", observed_error),
+            None => format!(
+                "unknown code {} for {}",
+                observed_code,
+                short_type_name::<E>()
+            ),
+        });
+
+    Err(DemandError::WrongError {
+        expected,
+        observed,
+        index: expected_index.map(|_| *observed_index),
+    })
+}
+
+/// Names a custom error from the [`error_registry`], if the program that returned it has a
+/// registered decoder that knows the code.
+///
+/// The caller's error enum only describes its own program's codes, so the registry's name
+/// is preferred for describing what was observed.
+fn registered_error_name(result: &TransactionResult) -> Option<String> {
+    error_registry::decode_error(result)
+        .filter(|decoded| decoded.name.is_some())
+        .map(|decoded| decoded.name_and_code())
+}
+
+/// Panics with the error's display output if a `check_*` function failed.
+pub(crate) fn or_panic(check: Result<(), DemandError>) {
+    if let Err(e) = check {
+        panic!("{}", e);
+    }
+}
+
+/// Panics like [`or_panic`], following the error with a report of the transaction.
+///
+/// The report is left out when [`report::DESCRIBE_FAILURES_ENV`] is set to `0`.
+pub(crate) fn or_panic_with(check: Result<(), DemandError>, result: &TransactionResult) {
+    if let Err(e) = check {
+        if report::describe_failures() {
+            panic!("{}\n\n{}", e, report::describe(result));
+        }
+        panic!("{}", e);
+    }
+}
+
+/// The Token-2022 program id, known without the optional `spl-token-2022` dependency.
+pub(crate) const TOKEN_2022_PROGRAM_ID: Pubkey =
+    solana_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Renders bytes as hex, eliding everything past the first 16 bytes.
+pub(crate) fn hex(data: &[u8]) -> String {
+    const SHOWN: usize = 16;
+
+    let mut out: String = data
+        .iter()
+        .take(SHOWN)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    if data.is_empty() {
+        out.push_str("(empty)");
+    } else if data.len() > SHOWN {
+        out.push_str(&format!("... ({} bytes)", data.len()));
+    }
+    out
+}
+
+/// Returns the last path segment of a type's name, e.g. `VaultError` for `my_program::error::VaultError`.
+pub(crate) fn short_type_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+/// Returns the logs of a transaction, whether it succeeded or failed.
+pub(crate) fn logs_of(result: &TransactionResult) -> &Vec<String> {
+    match result {
+        Ok(meta) => &meta.logs,
+        Err(meta) => &meta.meta.logs,
     }
 }
 
@@ -577,14 +2106,12 @@ pub fn demand_system_error_at_index(
 /// # Note
 ///
 /// This function is primarily intended for examples and getting started. For production
-/// tests, you may want more control over the setup process.
+/// tests, [`test_context::TestContext`] also manages actors, signing and blockhashes.
 pub fn setup_svm_and_fee_payer() -> (LiteSVM, Keypair) {
-    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
-
     let mut svm = LiteSVM::new();
 
     let fee_payer = Keypair::new();
-    svm.airdrop(&fee_payer.pubkey(), 100 * LAMPORTS_PER_SOL)
+    svm.airdrop(&fee_payer.pubkey(), test_context::DEFAULT_PAYER_BALANCE)
         .expect("airdrop failed");
 
     (svm, fee_payer)
@@ -0,0 +1,339 @@
+//! Structured parsing of Solana program logs.
+//!
+//! The runtime writes program logs as a flat list of lines, but the lines describe a tree:
+//! every `invoke [n]` opens an invocation at CPI depth `n`, and the matching `success` or
+//! `failed` line closes it. [`parse_logs`] rebuilds that tree so assertions can ask *which*
+//! program logged a line, and at what depth:
+//!
+//! ```text
+//! Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL invoke [1]
+//! Program log: Create
+//! Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]
+//! Program log: Instruction: GetAccountDataSize
+//! Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 1595 of 394600 compute units
+//! Program return: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA pQAAAAAAAAA=
+//! Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success
+//! Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL consumed 20484 of 400071 compute units
+//! Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL success
+//! ```
+//!
+//! parses into an associated token account invocation at depth 1 with one SPL Token child
+//! at depth 2:
+//!
+//! ```text
+//! let invocations = parse_logs(&result);
+//! let ata = &invocations[0];
+//! assert_eq!(ata.program_id, spl_associated_token_account::ID);
+//! assert_eq!(ata.children[0].program_id, spl_token::ID);
+//! assert_eq!(ata.children[0].logs, vec!["Instruction: GetAccountDataSize"]);
+//! ```
+
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use litesvm::types::TransactionResult;
+use solana_pubkey::Pubkey;
+
+use crate::logs_of;
+
+/// How a program invocation ended, according to the logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationStatus {
+    /// `Program <id> success`
+    Success,
+
+    /// `Program <id> failed: <reason>`, carrying the reason.
+    Failed(String),
+
+    /// The logs ended, or were truncated, before the invocation closed.
+    Incomplete,
+}
+
+/// One program invocation reconstructed from the logs, with the CPIs it made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInvocation {
+    /// The invoked program.
+    pub program_id: Pubkey,
+
+    /// CPI depth: 1 for top-level instructions, 2 for their CPIs, and so on.
+    pub depth: usize,
+
+    /// `Program log:` messages, with the prefix stripped.
+    pub logs: Vec<String>,
+
+    /// Decoded `Program data:` payloads. Each base64 field of each line is one entry.
+    pub data: Vec<Vec<u8>>,
+
+    /// Compute units consumed, from `consumed X of Y compute units`. Builtin programs don't
+    /// log this line.
+    pub compute_units_consumed: Option<u64>,
+
+    /// Compute units available to the invocation, the `Y` of the same line.
+    pub compute_units_limit: Option<u64>,
+
+    /// Decoded `Program return:` data set by this program.
+    pub return_data: Option<Vec<u8>>,
+
+    /// How the invocation ended.
+    pub status: InvocationStatus,
+
+    /// Invocations made by this program via CPI, in execution order.
+    pub children: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+    fn new(program_id: Pubkey, depth: usize) -> Self {
+        ProgramInvocation {
+            program_id,
+            depth,
+            logs: Vec::new(),
+            data: Vec::new(),
+            compute_units_consumed: None,
+            compute_units_limit: None,
+            return_data: None,
+            status: InvocationStatus::Incomplete,
+            children: Vec::new(),
+        }
+    }
+
+    /// Whether the invocation logged `success`.
+    pub fn succeeded(&self) -> bool {
+        self.status == InvocationStatus::Success
+    }
+
+    /// This invocation followed by all of its descendants, depth-first in execution order.
+    pub fn iter(&self) -> impl Iterator<Item = &ProgramInvocation> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(next.children.iter().rev());
+            Some(next)
+        })
+    }
+}
+
+/// Parses a transaction's logs into one [`ProgramInvocation`] tree per top-level instruction.
+///
+/// Works with both successful and failed transactions. Instructions after a failing one never
+/// run, so they don't appear.
+pub fn parse_logs(result: &TransactionResult) -> Vec<ProgramInvocation> {
+    parse_log_lines(logs_of(result))
+}
+
+/// Returns the program whose failure made the transaction fail, according to the logs.
+///
+/// A failing CPI aborts every invocation above it, and each of them logs `failed` with the
+/// same reason, so this is the innermost failed invocation. Returns `None` if the
+/// transaction succeeded, or its logs were truncated before any invocation failed.
+pub fn failed_program(result: &TransactionResult) -> Option<Pubkey> {
+    parse_logs(result)
+        .iter()
+        .flat_map(|root| root.iter())
+        .filter(|invocation| matches!(invocation.status, InvocationStatus::Failed(_)))
+        .last()
+        .map(|invocation| invocation.program_id)
+}
+
+/// Parses raw log lines into invocation trees.
+///
+/// Lines that don't belong to an open invocation, or that aren't in a recognised format, are
+/// skipped. Invocations still open when the lines run out are closed as
+/// [`InvocationStatus::Incomplete`].
+pub fn parse_log_lines(lines: &[String]) -> Vec<ProgramInvocation> {
+    let mut roots = Vec::new();
+    let mut open: Vec<ProgramInvocation> = Vec::new();
+
+    for line in lines {
+        if let Some(message) = line.strip_prefix("Program log: ") {
+            if let Some(current) = open.last_mut() {
+                current.logs.push(message.to_string());
+            }
+        } else if let Some(data) = line.strip_prefix("Program data: ") {
+            if let Some(current) = open.last_mut() {
+                current.data.extend(
+                    data.split_whitespace()
+                        .filter_map(|chunk| STANDARD.decode(chunk).ok()),
+                );
+            }
+        } else if let Some(rest) = line.strip_prefix("Program return: ") {
+            let decoded = rest
+                .split_once(' ')
+                .and_then(|(_, data)| STANDARD.decode(data).ok());
+            if let Some(current) = open.last_mut() {
+                current.return_data = decoded;
+            }
+        } else if let Some((program_id, event)) = line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+            .and_then(|(id, event)| Some((Pubkey::from_str(id).ok()?, event)))
+        {
+            apply_event(program_id, event, &mut open, &mut roots);
+        }
+    }
+
+    while let Some(unclosed) = open.pop() {
+        close(unclosed, &mut open, &mut roots);
+    }
+
+    roots
+}
+
+fn apply_event(
+    program_id: Pubkey,
+    event: &str,
+    open: &mut Vec<ProgramInvocation>,
+    roots: &mut Vec<ProgramInvocation>,
+) {
+    if let Some(depth) = event
+        .strip_prefix("invoke [")
+        .and_then(|depth| depth.strip_suffix(']'))
+        .and_then(|depth| depth.parse().ok())
+    {
+        // A sibling or shallower invoke means earlier invocations closed without logging it
+        while open.last().is_some_and(|current| current.depth >= depth) {
+            let unclosed = open.pop().unwrap();
+            close(unclosed, open, roots);
+        }
+        open.push(ProgramInvocation::new(program_id, depth));
+    } else if let Some(consumed) = event.strip_prefix("consumed ") {
+        let mut numbers = consumed.split(' ').filter_map(|n| n.parse().ok());
+        if let Some(current) = open.last_mut() {
+            current.compute_units_consumed = numbers.next();
+            current.compute_units_limit = numbers.next();
+        }
+    } else if event == "success" || event.starts_with("failed") {
+        let status = match event.strip_prefix("failed: ") {
+            Some(reason) => InvocationStatus::Failed(reason.to_string()),
+            None if event == "success" => InvocationStatus::Success,
+            None => InvocationStatus::Failed(String::new()),
+        };
+
+        // A program can fail before it is invoked (e.g. it isn't deployed); record it anyway
+        let mut invocation = match open.pop() {
+            Some(invocation) => invocation,
+            None => ProgramInvocation::new(program_id, 1),
+        };
+        invocation.status = status;
+        close(invocation, open, roots);
+    }
+}
+
+fn close(
+    invocation: ProgramInvocation,
+    open: &mut [ProgramInvocation],
+    roots: &mut Vec<ProgramInvocation>,
+) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(invocation),
+        None => roots.push(invocation),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const SYSTEM: &str = "11111111111111111111111111111111";
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn pubkey(id: &str) -> Pubkey {
+        Pubkey::from_str(id).unwrap()
+    }
+
+    #[test]
+    fn test_nested_invocations() {
+        let logs = lines(&[
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 success",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS invoke [1]",
+            "Program log: Instruction: Deposit",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 189305 compute units",
+            "Program return: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA AQI=",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program data: AQID BAU=",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS consumed 15000 of 199850 compute units",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS success",
+        ]);
+
+        let invocations = parse_log_lines(&logs);
+        assert_eq!(invocations.len(), 2);
+
+        let system = &invocations[0];
+        assert_eq!(system.program_id, pubkey(SYSTEM));
+        assert!(system.succeeded());
+        assert_eq!(system.compute_units_consumed, None);
+
+        let program = &invocations[1];
+        assert_eq!(program.program_id, pubkey(PROGRAM));
+        assert_eq!(program.depth, 1);
+        assert_eq!(program.logs, vec!["Instruction: Deposit"]);
+        assert_eq!(program.data, vec![vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(program.compute_units_consumed, Some(15000));
+        assert_eq!(program.compute_units_limit, Some(199850));
+        assert_eq!(program.return_data, None);
+
+        let token = &program.children[0];
+        assert_eq!(token.program_id, pubkey(TOKEN));
+        assert_eq!(token.depth, 2);
+        assert_eq!(token.logs, vec!["Instruction: Transfer"]);
+        assert_eq!(token.return_data, Some(vec![1, 2]));
+        assert_eq!(token.compute_units_consumed, Some(4645));
+
+        let order: Vec<_> = program.iter().map(|i| i.program_id).collect();
+        assert_eq!(order, vec![pubkey(PROGRAM), pubkey(TOKEN)]);
+    }
+
+    #[test]
+    fn test_failed_and_truncated_invocations() {
+        let logs = lines(&[
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Error: insufficient funds",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS invoke [1]",
+            "Program log: Instruction: Retry",
+            "Log truncated",
+        ]);
+
+        let invocations = parse_log_lines(&logs);
+        assert_eq!(invocations.len(), 2);
+
+        // The outer invocation never logged its own outcome
+        assert_eq!(invocations[0].status, InvocationStatus::Incomplete);
+        assert_eq!(
+            invocations[0].children[0].status,
+            InvocationStatus::Failed("custom program error: 0x1".to_string())
+        );
+        assert_eq!(invocations[1].status, InvocationStatus::Incomplete);
+        assert_eq!(invocations[1].logs, vec!["Instruction: Retry"]);
+    }
+
+    #[test]
+    fn test_failed_program_is_innermost() {
+        let logs = lines(&[
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS invoke [1]",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS consumed 9000 of 200000 compute units",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS failed: custom program error: 0x1",
+        ]);
+
+        let mut meta = litesvm::types::TransactionMetadata {
+            logs,
+            ..Default::default()
+        };
+        assert_eq!(failed_program(&Ok(meta.clone())), Some(pubkey(TOKEN)));
+
+        meta.logs.truncate(3);
+        assert_eq!(failed_program(&Ok(meta)), None);
+    }
+}
@@ -0,0 +1,381 @@
+//! Scoped and pattern log assertions for LiteSVM.
+//!
+//! ## Scoped assertions
+//!
+//! [`demand_logs_contain_at_index`](crate::demand_logs_contain_at_index) pins a message to a
+//! raw log line, so adding a CPI or an extra `msg!` shifts every index after it. The
+//! assertions here use [`parse_logs`] to scope the search to the invocations that matter
+//! instead:
+//!
+//! ```text
+//! // Anywhere the token program logged it, at any CPI depth
+//! demand_program_logged(&spl_token::ID, "Instruction: Transfer", result);
+//!
+//! // Only when the token program was invoked via CPI from a top-level instruction
+//! demand_logged_at_depth(&spl_token::ID, 2, "Instruction: Transfer", result);
+//!
+//! // Anything logged while the second instruction ran, including its CPIs
+//! demand_instruction_logged(1, "Instruction: Transfer", result);
+//! ```
+//!
+//! Only `Program log:` messages are searched, with the prefix stripped. On failure the panic
+//! message lists the messages of the relevant invocations rather than the whole log.
+//!
+//! ## Pattern assertions
+//!
+//! Where [`demand_logs_contain`](crate::demand_logs_contain) matches one substring, these
+//! match regular expressions, ordered sequences, absences, and counts over the raw log lines:
+//!
+//! ```text
+//! demand_logs_match(r"Transferred \d+ tokens", result);
+//! demand_logs_in_order(&["Instruction: Deposit", "Transfer", "Deposit complete"], result);
+//! demand_logs_not_contain("Error", result);
+//! demand_log_count("Instruction: Transfer", 2, result);
+//! ```
+//!
+//! A broken sequence reports which items matched, at which log index, and which item was
+//! never found after them.
+
+use litesvm::types::TransactionResult;
+use regex::Regex;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    log_parser::{parse_logs, ProgramInvocation},
+    logs_of, or_panic_with, DemandError,
+};
+
+/// Asserts that `program_id` logged a message containing `expected`, at any depth.
+///
+/// Only the program's own messages are searched, not those of programs it invoked.
+///
+/// # Arguments
+///
+/// * `program_id` - The program that should have logged the message
+/// * `expected` - The string to search for within the program's log messages
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the program was never invoked, or none of its messages contain `expected`.
+///
+/// # Example
+///
+/// ```text
+/// demand_program_logged(&spl_token::ID, "Instruction: Transfer", result);
+/// ```
+pub fn demand_program_logged(program_id: &Pubkey, expected: &str, result: TransactionResult) {
+    or_panic_with(check_program_logged(program_id, expected, &result), &result);
+}
+
+/// Checks that `program_id` logged a message containing `expected`, without panicking.
+///
+/// Non-panicking version of [`demand_program_logged`].
+pub fn check_program_logged(
+    program_id: &Pubkey,
+    expected: &str,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let invocations = parse_logs(result);
+    let scoped = invocations
+        .iter()
+        .flat_map(ProgramInvocation::iter)
+        .filter(|invocation| invocation.program_id == *program_id);
+
+    check_scoped(format!("program {}", program_id), scoped, expected)
+}
+
+/// Asserts that `program_id`, invoked at CPI `depth`, logged a message containing `expected`.
+///
+/// Depth 1 is a top-level instruction, depth 2 a CPI made by one, and so on.
+///
+/// # Panics
+///
+/// Panics if the program was never invoked at `depth`, or none of its messages there contain
+/// `expected`.
+///
+/// # Example
+///
+/// ```text
+/// // The vault program CPIs into the token program
+/// demand_logged_at_depth(&spl_token::ID, 2, "Instruction: Transfer", result);
+/// ```
+pub fn demand_logged_at_depth(
+    program_id: &Pubkey,
+    depth: usize,
+    expected: &str,
+    result: TransactionResult,
+) {
+    or_panic_with(
+        check_logged_at_depth(program_id, depth, expected, &result),
+        &result,
+    );
+}
+
+/// Checks that `program_id`, invoked at `depth`, logged a message containing `expected`,
+/// without panicking.
+///
+/// Non-panicking version of [`demand_logged_at_depth`].
+pub fn check_logged_at_depth(
+    program_id: &Pubkey,
+    depth: usize,
+    expected: &str,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let invocations = parse_logs(result);
+    let scoped = invocations
+        .iter()
+        .flat_map(ProgramInvocation::iter)
+        .filter(|invocation| invocation.program_id == *program_id && invocation.depth == depth);
+
+    check_scoped(
+        format!("program {} at depth {}", program_id, depth),
+        scoped,
+        expected,
+    )
+}
+
+/// Asserts that a message containing `expected` was logged while the top-level instruction
+/// at `expected_index` ran.
+///
+/// Messages from every program the instruction invoked via CPI count.
+///
+/// # Arguments
+///
+/// * `expected_index` - The index of the instruction (0-based)
+/// * `expected` - The string to search for within the instruction's log messages
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the instruction never ran, or none of its messages contain `expected`.
+///
+/// # Example
+///
+/// ```text
+/// demand_instruction_logged(1, "Instruction: Transfer", result);
+/// ```
+pub fn demand_instruction_logged(expected_index: u8, expected: &str, result: TransactionResult) {
+    or_panic_with(
+        check_instruction_logged(expected_index, expected, &result),
+        &result,
+    );
+}
+
+/// Checks that a message containing `expected` was logged inside the instruction at
+/// `expected_index`, without panicking.
+///
+/// Non-panicking version of [`demand_instruction_logged`].
+pub fn check_instruction_logged(
+    expected_index: u8,
+    expected: &str,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let invocations = parse_logs(result);
+    let scoped = invocations
+        .get(expected_index as usize)
+        .into_iter()
+        .flat_map(ProgramInvocation::iter);
+
+    check_scoped(format!("instruction {}", expected_index), scoped, expected)
+}
+
+fn check_scoped<'a>(
+    scope: String,
+    invocations: impl Iterator<Item = &'a ProgramInvocation>,
+    expected: &str,
+) -> Result<(), DemandError> {
+    let mut found = false;
+    let mut logs = Vec::new();
+
+    for invocation in invocations {
+        found = true;
+        logs.extend(invocation.logs.iter().cloned());
+    }
+
+    if logs.iter().any(|log| log.contains(expected)) {
+        return Ok(());
+    }
+
+    Err(DemandError::ScopedLogNotFound {
+        expected: expected.to_string(),
+        scope,
+        logs: found.then_some(logs),
+    })
+}
+
+/// Asserts that at least one log line matches the regular expression `pattern`.
+///
+/// # Arguments
+///
+/// * `pattern` - A [`regex`] pattern, matched anywhere within each log line
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if `pattern` is not a valid regular expression, or no log line matches it.
+///
+/// # Example
+///
+/// ```text
+/// demand_logs_match(r"Transferred \d+ tokens", result);
+/// ```
+pub fn demand_logs_match(pattern: &str, result: TransactionResult) {
+    or_panic_with(check_logs_match(pattern, &result), &result);
+}
+
+/// Checks that at least one log line matches `pattern`, without panicking.
+///
+/// Non-panicking version of [`demand_logs_match`].
+///
+/// # Errors
+///
+/// - [`DemandError::InvalidPattern`] if `pattern` doesn't compile
+/// - [`DemandError::PatternNotFound`] carrying every log entry if no line matches
+pub fn check_logs_match(pattern: &str, result: &TransactionResult) -> Result<(), DemandError> {
+    let regex = Regex::new(pattern).map_err(|e| DemandError::InvalidPattern {
+        pattern: pattern.to_string(),
+        error: e.to_string(),
+    })?;
+
+    let logs = logs_of(result);
+    if logs.iter().any(|log| regex.is_match(log)) {
+        return Ok(());
+    }
+
+    Err(DemandError::PatternNotFound {
+        pattern: pattern.to_string(),
+        logs: logs.clone(),
+    })
+}
+
+/// Asserts that log lines containing each of `expected` appear in order.
+///
+/// This is a subsequence match: other lines may appear between the expected ones, and each
+/// expected string must be found on a line after the one that matched its predecessor.
+///
+/// # Panics
+///
+/// Panics if the sequence breaks. The panic message lists where each item matched, the
+/// first item that couldn't be found, and the full logs.
+///
+/// # Example
+///
+/// ```text
+/// demand_logs_in_order(&["Instruction: Deposit", "Transfer", "Deposit complete"], result);
+/// ```
+pub fn demand_logs_in_order(expected: &[&str], result: TransactionResult) {
+    or_panic_with(check_logs_in_order(expected, &result), &result);
+}
+
+/// Checks that log lines containing each of `expected` appear in order, without panicking.
+///
+/// Non-panicking version of [`demand_logs_in_order`].
+///
+/// # Errors
+///
+/// Returns [`DemandError::LogSequenceBroken`] with the log index of every item matched
+/// before the sequence broke.
+pub fn check_logs_in_order(
+    expected: &[&str],
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let logs = logs_of(result);
+    let mut matched = Vec::with_capacity(expected.len());
+    let mut next = 0;
+
+    for item in expected {
+        let Some(offset) = logs[next..].iter().position(|log| log.contains(item)) else {
+            return Err(DemandError::LogSequenceBroken {
+                expected: expected.iter().map(|item| item.to_string()).collect(),
+                matched,
+                logs: logs.clone(),
+            });
+        };
+
+        matched.push(next + offset);
+        next += offset + 1;
+    }
+
+    Ok(())
+}
+
+/// Asserts that no log line contains `unexpected`.
+///
+/// # Panics
+///
+/// Panics if any log line contains `unexpected`, listing the offending lines.
+///
+/// # Example
+///
+/// ```text
+/// demand_logs_not_contain("Error", result);
+/// ```
+pub fn demand_logs_not_contain(unexpected: &str, result: TransactionResult) {
+    or_panic_with(check_logs_not_contain(unexpected, &result), &result);
+}
+
+/// Checks that no log line contains `unexpected`, without panicking.
+///
+/// Non-panicking version of [`demand_logs_not_contain`].
+pub fn check_logs_not_contain(
+    unexpected: &str,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let matches = matching_lines(unexpected, result);
+
+    if matches.is_empty() {
+        return Ok(());
+    }
+
+    Err(DemandError::UnexpectedLog {
+        unexpected: unexpected.to_string(),
+        matches,
+    })
+}
+
+/// Asserts that exactly `count` log lines contain `expected`.
+///
+/// # Panics
+///
+/// Panics if a different number of lines contain `expected`, listing the lines that did.
+///
+/// # Example
+///
+/// ```text
+/// // One Transfer per leg of the swap
+/// demand_log_count("Instruction: Transfer", 2, result);
+/// ```
+pub fn demand_log_count(expected: &str, count: usize, result: TransactionResult) {
+    or_panic_with(check_log_count(expected, count, &result), &result);
+}
+
+/// Checks that exactly `count` log lines contain `expected`, without panicking.
+///
+/// Non-panicking version of [`demand_log_count`].
+pub fn check_log_count(
+    expected: &str,
+    count: usize,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let matches = matching_lines(expected, result);
+
+    if matches.len() == count {
+        return Ok(());
+    }
+
+    Err(DemandError::WrongLogCount {
+        expected: expected.to_string(),
+        expected_count: count,
+        matches,
+    })
+}
+
+fn matching_lines(expected: &str, result: &TransactionResult) -> Vec<(usize, String)> {
+    logs_of(result)
+        .iter()
+        .enumerate()
+        .filter(|(_, log)| log.contains(expected))
+        .map(|(i, log)| (i, log.clone()))
+        .collect()
+}
@@ -0,0 +1,272 @@
+//! Human-readable reports of transaction results.
+//!
+//! A failing assertion says what went wrong; the report shows what the transaction did.
+//! [`describe`] renders a result's signature, status, decoded error, fee and compute units,
+//! the invocation tree with each program's logs and CU, the inner instructions and the
+//! return data:
+//!
+//! ```text
+//! Transaction 4cBhKnQnpkaZo7vGGhbg8dkh1rBWN1RXNkGZpoNCXAQ3xmxPW4WFoZdTZv9ysULdVUfD7w2TTLCGD68Wzgjrm2GL
+//!   status:        failed: Error processing Instruction 2: custom program error: 0x6
+//!   error:         spl_token::AlreadyInUse (0x6) in instruction 2
+//!   fee:           unknown without the transaction
+//!   compute units: 5141
+//!   invocations:
+//!     [0] system_program: success
+//!     [1] spl_token: success, 2779 of 402850 CU
+//!           log: Instruction: InitializeMint2
+//!     [2] spl_token: failed: custom program error: 0x6, 2212 of 400071 CU
+//!           log: Instruction: InitializeMint2
+//!           log: Error: account or token already in use
+//!   inner instructions: none
+//!   return data:   none
+//! ```
+//!
+//! Every `demand_*` assertion on a transaction result prints this report after its failure
+//! message. Set `LITESVM_DESCRIBE_FAILURES=0` to leave it out.
+//!
+//! Use [`TransactionReport`] to add the fee, which needs the transaction, and to name
+//! accounts from an address book:
+//!
+//! ```text
+//! let report = TransactionReport::new(&result)
+//!     .transaction(&tx)
+//!     .address_book(&book);
+//! println!("{report}");
+//! ```
+
+use std::fmt;
+
+use litesvm::types::TransactionResult;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+use crate::{
+    address_book::AddressBook,
+    balance_testing::transaction_fee,
+    cpi_testing::cpis,
+    error_registry::decode_error,
+    hex,
+    log_parser::{parse_logs, InvocationStatus, ProgramInvocation},
+    return_data_testing::return_data,
+};
+
+/// Environment variable that, when set to `0`, leaves the transaction report out of
+/// assertion failures.
+pub const DESCRIBE_FAILURES_ENV: &str = "LITESVM_DESCRIBE_FAILURES";
+
+/// Whether assertion failures should include a transaction report.
+pub(crate) fn describe_failures() -> bool {
+    std::env::var(DESCRIBE_FAILURES_ENV).map_or(true, |value| value != "0")
+}
+
+/// Renders a transaction result as a multi-line report.
+///
+/// The fee is only known with the transaction; see [`TransactionReport::transaction`].
+///
+/// Pubkeys are named from the shared address book; see [`AddressBook::share`].
+pub fn describe(result: &TransactionResult) -> String {
+    TransactionReport::new(result).to_string()
+}
+
+/// A configurable, displayable report of a transaction result.
+///
+/// [`TransactionReport::new`] starts from the shared address book, which names the
+/// well-known programs and sysvars.
+pub struct TransactionReport<'a> {
+    result: &'a TransactionResult,
+    transaction: Option<&'a Transaction>,
+    address_book: AddressBook,
+}
+
+impl<'a> TransactionReport<'a> {
+    /// Creates a report of `result`.
+    pub fn new(result: &'a TransactionResult) -> Self {
+        TransactionReport {
+            result,
+            transaction: None,
+            address_book: AddressBook::shared(),
+        }
+    }
+
+    /// Adds the transaction that produced the result, for its fee and to resolve the
+    /// accounts of inner instructions.
+    pub fn transaction(mut self, transaction: &'a Transaction) -> Self {
+        self.transaction = Some(transaction);
+        self
+    }
+
+    /// Shows every pubkey in `address_book` under its label.
+    pub fn address_book(mut self, address_book: &AddressBook) -> Self {
+        self.address_book.merge(address_book);
+        self
+    }
+
+    fn name(&self, pubkey: &Pubkey) -> String {
+        self.address_book.name(pubkey)
+    }
+
+    fn write_invocation(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        label: &str,
+        invocation: &ProgramInvocation,
+        indent: usize,
+    ) -> fmt::Result {
+        let status = match &invocation.status {
+            InvocationStatus::Success => "success".to_string(),
+            InvocationStatus::Failed(reason) if reason.is_empty() => "failed".to_string(),
+            InvocationStatus::Failed(reason) => format!("failed: {}", reason),
+            InvocationStatus::Incomplete => "incomplete".to_string(),
+        };
+        let compute_units = match (
+            invocation.compute_units_consumed,
+            invocation.compute_units_limit,
+        ) {
+            (Some(consumed), Some(limit)) => format!(", {} of {} CU", consumed, limit),
+            (Some(consumed), None) => format!(", {} CU", consumed),
+            _ => String::new(),
+        };
+
+        writeln!(
+            f,
+            "{:indent$}{} {}: {}{}",
+            "",
+            label,
+            self.name(&invocation.program_id),
+            status,
+            compute_units
+        )?;
+
+        let detail = indent + 6;
+        for log in &invocation.logs {
+            writeln!(f, "{:detail$}log: {}", "", log)?;
+        }
+        for data in &invocation.data {
+            writeln!(f, "{:detail$}data: {}", "", hex(data))?;
+        }
+        if let Some(return_data) = &invocation.return_data {
+            writeln!(f, "{:detail$}return: {}", "", hex(return_data))?;
+        }
+        for child in &invocation.children {
+            self.write_invocation(f, "->", child, indent + 4)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TransactionReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let meta = match self.result {
+            Ok(meta) => meta,
+            Err(e) => &e.meta,
+        };
+
+        writeln!(f, "Transaction {}", meta.signature)?;
+        match self.result {
+            Ok(_) => writeln!(f, "  status:        succeeded")?,
+            Err(e) => {
+                writeln!(f, "  status:        failed: {}", e.err)?;
+                if let Some(decoded) = decode_error(self.result) {
+                    writeln!(f, "  error:         {}", decoded)?;
+                }
+            }
+        }
+        match self.transaction {
+            Some(tx) => writeln!(f, "  fee:           {} lamports", transaction_fee(tx))?,
+            None => writeln!(f, "  fee:           unknown without the transaction")?,
+        }
+        writeln!(f, "  compute units: {}", meta.compute_units_consumed)?;
+
+        let invocations = parse_logs(self.result);
+        if invocations.is_empty() {
+            writeln!(f, "  invocations:   none logged")?;
+        } else {
+            writeln!(f, "  invocations:")?;
+            for (index, invocation) in invocations.iter().enumerate() {
+                self.write_invocation(f, &format!("[{}]", index), invocation, 4)?;
+            }
+        }
+
+        match cpis(self.result) {
+            Err(e) => writeln!(f, "  inner instructions: {}", e)?,
+            Ok(cpis) if cpis.is_empty() => writeln!(f, "  inner instructions: none")?,
+            Ok(cpis) => {
+                let account_keys = self.transaction.map(|tx| &tx.message.account_keys);
+                writeln!(f, "  inner instructions:")?;
+                for cpi in cpis {
+                    let accounts: Vec<String> = cpi
+                        .accounts
+                        .iter()
+                        .map(
+                            |i| match account_keys.and_then(|keys| keys.get(*i as usize)) {
+                                Some(key) => self.name(key),
+                                None => format!("#{}", i),
+                            },
+                        )
+                        .collect();
+                    writeln!(
+                        f,
+                        "    [{}] {} at depth {}, data {}, accounts [{}]",
+                        cpi.instruction_index,
+                        self.name(&cpi.program_id),
+                        cpi.depth,
+                        hex(&cpi.data),
+                        accounts.join(", ")
+                    )?;
+                }
+            }
+        }
+
+        match return_data(self.result) {
+            Some((program_id, data)) => write!(
+                f,
+                "  return data:   {} from {}",
+                hex(data),
+                self.name(&program_id)
+            ),
+            None => write!(f, "  return data:   none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use litesvm::types::TransactionMetadata;
+
+    use super::*;
+
+    fn succeeded(logs: &[&str]) -> TransactionMetadata {
+        TransactionMetadata {
+            logs: logs.iter().map(|log| log.to_string()).collect(),
+            compute_units_consumed: 4_645,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_describe_invocation_tree() {
+        let vault = Pubkey::new_unique();
+        let result = Ok(succeeded(&[
+            &format!("Program {} invoke [1]", vault),
+            "Program log: Instruction: Deposit",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 189305 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            &format!("Program {} success", vault),
+        ]));
+
+        let report = TransactionReport::new(&result)
+            .address_book(&AddressBook::empty().with(vault, "vault"))
+            .to_string();
+
+        assert!(report.contains("  status:        succeeded\n"));
+        assert!(report.contains("  fee:           unknown without the transaction\n"));
+        assert!(report.contains("  compute units: 4645\n"));
+        assert!(report.contains(
+            "    [0] vault: success\n          log: Instruction: Deposit\n        -> spl_token: success, 4645 of 189305 CU\n              log: Instruction: Transfer\n"
+        ));
+        assert!(report.ends_with("  return data:   none"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Return data assertions for LiteSVM.
+//!
+//! Programs hand results back to their caller with `set_return_data`. The runtime reports
+//! the return data left when the transaction finished, along with the program that set it:
+//!
+//! ```text
+//! // Raw bytes
+//! demand_return_data(&my_program::ID, &42u64.to_le_bytes(), result);
+//!
+//! // Borsh-decoded into a Rust type
+//! demand_return_data_decodes::<Quote>(|q| q.amount_out >= 1_000, result);
+//! ```
+//!
+//! Every BPF program invocation clears the return data when it starts, so this is the data
+//! set by the last program invoked, which is not necessarily the top-level one: a program
+//! that CPIs after calling `set_return_data` loses it. [`parse_logs`](crate::log_parser::parse_logs)
+//! records each invocation's `Program return:` line if you need an earlier value.
+//!
+//! For Anchor view instructions, see `anchor_testing::return_value` (`anchor` feature).
+
+use borsh::BorshDeserialize;
+use litesvm::types::TransactionResult;
+use solana_pubkey::Pubkey;
+
+use crate::{hex, or_panic_with, DemandError};
+
+/// Returns the program that set the transaction's return data, and the data.
+///
+/// Returns `None` if no program set any return data, or the last one set it empty.
+/// Works with both successful and failed transactions.
+pub fn return_data(result: &TransactionResult) -> Option<(Pubkey, &[u8])> {
+    let return_data = match result {
+        Ok(meta) => &meta.return_data,
+        Err(e) => &e.meta.return_data,
+    };
+
+    if return_data.data.is_empty() {
+        return None;
+    }
+
+    Some((return_data.program_id, &return_data.data))
+}
+
+/// Borsh-decodes the transaction's return data as `T`.
+///
+/// Returns `None` if there is no return data, or it doesn't decode as exactly one `T`.
+pub fn decode_return_data<T: BorshDeserialize>(result: &TransactionResult) -> Option<T> {
+    let (_, data) = return_data(result)?;
+    T::try_from_slice(data).ok()
+}
+
+/// Asserts that `program_id` set the transaction's return data to exactly `expected`.
+///
+/// # Arguments
+///
+/// * `program_id` - The program that should have set the return data
+/// * `expected` - The exact bytes expected
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if there is no return data, or a different program or different bytes were
+/// returned.
+///
+/// # Example
+///
+/// ```text
+/// demand_return_data(&my_program::ID, &42u64.to_le_bytes(), result);
+/// ```
+pub fn demand_return_data(program_id: &Pubkey, expected: &[u8], result: TransactionResult) {
+    or_panic_with(check_return_data(program_id, expected, &result), &result);
+}
+
+/// Checks that `program_id` set the return data to exactly `expected`, without panicking.
+///
+/// Non-panicking version of [`demand_return_data`].
+pub fn check_return_data(
+    program_id: &Pubkey,
+    expected: &[u8],
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let expected_desc = format!("return data {} from {}", hex(expected), program_id);
+
+    let Some((observed_program, data)) = return_data(result) else {
+        return Err(DemandError::ReturnDataMissing {
+            expected: expected_desc,
+        });
+    };
+
+    if observed_program == *program_id && data == expected {
+        return Ok(());
+    }
+
+    Err(DemandError::ReturnDataMismatch {
+        expected: expected_desc,
+        program_id: observed_program,
+        data: data.to_vec(),
+        error: None,
+    })
+}
+
+/// Asserts that the transaction's return data Borsh-decodes as `T` and satisfies `predicate`.
+///
+/// The data must decode as exactly one `T`, with no trailing bytes. Use `|_| true` to only
+/// require that it decodes.
+///
+/// # Arguments
+///
+/// * `predicate` - Condition the decoded value must satisfy
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if there is no return data, it doesn't decode as `T`, or the decoded value
+/// doesn't satisfy `predicate`.
+///
+/// # Example
+///
+/// ```text
+/// demand_return_data_decodes::<u64>(|balance| *balance == 1_000, result);
+/// ```
+pub fn demand_return_data_decodes<T: BorshDeserialize>(
+    predicate: impl Fn(&T) -> bool,
+    result: TransactionResult,
+) {
+    or_panic_with(check_return_data_decodes(predicate, &result), &result);
+}
+
+/// Checks that the return data decodes as `T` and satisfies `predicate`, without panicking.
+///
+/// Non-panicking version of [`demand_return_data_decodes`].
+pub fn check_return_data_decodes<T: BorshDeserialize>(
+    predicate: impl Fn(&T) -> bool,
+    result: &TransactionResult,
+) -> Result<(), DemandError> {
+    let type_name = std::any::type_name::<T>();
+
+    let Some((program_id, data)) = return_data(result) else {
+        return Err(DemandError::ReturnDataMissing {
+            expected: format!("return data decoding as {}", type_name),
+        });
+    };
+
+    let mismatch = |expected: String, error: Option<String>| DemandError::ReturnDataMismatch {
+        expected,
+        program_id,
+        data: data.to_vec(),
+        error,
+    };
+
+    match T::try_from_slice(data) {
+        Ok(value) if predicate(&value) => Ok(()),
+        Ok(_) => Err(mismatch(
+            format!(
+                "return data decoding as {} matching the predicate",
+                type_name
+            ),
+            None,
+        )),
+        Err(e) => Err(mismatch(
+            format!("return data decoding as {}", type_name),
+            Some(e.to_string()),
+        )),
+    }
+}
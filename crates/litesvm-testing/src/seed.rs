@@ -0,0 +1,134 @@
+//! Deterministic keypairs from a seed and a label.
+//!
+//! `Keypair::new()` gives every run different pubkeys, so logs, snapshots and benchmark
+//! output change from run to run. [`seeded_keypair`] derives the keypair from a seed and a
+//! label instead: the same seed and label always give the same keypair.
+//!
+//! ```text
+//! let seed = test_seed();                    // LITESVM_SEED, or 0
+//! let mint = seeded_keypair(seed, "mint");   // the same pubkey every run
+//! ```
+//!
+//! [`TestContext`](crate::test_context::TestContext) derives its payer, actors and
+//! [`keypair`](crate::test_context::TestContext::keypair)s this way, and prints its seed
+//! when the test fails. Elsewhere, a [`SeedGuard`] does the printing:
+//!
+//! ```text
+//! let seed = SeedGuard::new(test_seed());
+//! let sender = seeded_keypair(seed.seed(), "sender");
+//! ```
+//!
+//! Set [`SEED_ENV`] to the printed seed to replay the run:
+//!
+//! ```text
+//! LITESVM_SEED=8126536403452341271 cargo test deposit_fuzz
+//! LITESVM_SEED=random cargo test                     # a fresh seed per test
+//! ```
+
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use solana_keypair::{keypair_from_seed, Keypair};
+
+/// Environment variable holding the seed: a `u64`, or `random` for a fresh seed per test.
+pub const SEED_ENV: &str = "LITESVM_SEED";
+
+/// The seed used when [`SEED_ENV`] isn't set.
+pub const DEFAULT_SEED: u64 = 0;
+
+thread_local! {
+    /// The seed [`test_seed`] resolved on this thread, which runs one test at a time.
+    static THREAD_SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Returns the seed for this test.
+///
+/// [`DEFAULT_SEED`] unless [`SEED_ENV`] is set; `random` picks a fresh seed per test. The
+/// seed is resolved once per thread, and the test harness runs each test on its own thread,
+/// so every call in a test returns the same seed.
+///
+/// # Panics
+///
+/// Panics if [`SEED_ENV`] is neither a `u64` nor `random`.
+pub fn test_seed() -> u64 {
+    THREAD_SEED.with(|thread_seed| {
+        thread_seed.get().unwrap_or_else(|| {
+            let seed = resolve_seed();
+            thread_seed.set(Some(seed));
+            seed
+        })
+    })
+}
+
+fn resolve_seed() -> u64 {
+    match std::env::var(SEED_ENV) {
+        Err(_) => DEFAULT_SEED,
+        Ok(value) if value == "random" => RandomState::new().build_hasher().finish(),
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            panic!(
+                "{} must be a u64 or \"random\" but is {:?}",
+                SEED_ENV, value
+            )
+        }),
+    }
+}
+
+/// Prints its seed if the test panics while the guard is alive, so the run can be replayed.
+#[must_use = "the seed is only printed while the guard is alive"]
+#[derive(Debug)]
+pub struct SeedGuard(u64);
+
+impl SeedGuard {
+    /// Guards `seed`.
+    pub fn new(seed: u64) -> Self {
+        SeedGuard(seed)
+    }
+
+    /// The guarded seed.
+    pub fn seed(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Drop for SeedGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("seed: {} (replay with {}={})", self.0, SEED_ENV, self.0);
+        }
+    }
+}
+
+/// Derives the keypair for `label` from `seed`.
+///
+/// Different labels give unrelated keypairs; the same seed and label always give the same
+/// one.
+pub fn seeded_keypair(seed: u64, label: &str) -> Keypair {
+    let secret = solana_sha256_hasher::hashv(&[&seed.to_le_bytes(), label.as_bytes()]);
+    keypair_from_seed(secret.as_ref()).expect("a 32-byte seed derives a keypair")
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_signer::Signer;
+
+    use super::*;
+
+    #[test]
+    fn test_seeded_keypairs_are_deterministic() {
+        assert_eq!(
+            seeded_keypair(7, "mint").pubkey(),
+            seeded_keypair(7, "mint").pubkey()
+        );
+        assert_ne!(
+            seeded_keypair(7, "mint").pubkey(),
+            seeded_keypair(8, "mint").pubkey()
+        );
+        assert_ne!(
+            seeded_keypair(7, "mint").pubkey(),
+            seeded_keypair(7, "payer").pubkey()
+        );
+    }
+}
@@ -0,0 +1,400 @@
+//! Log snapshot assertions for LiteSVM.
+//!
+//! For programs with long, intricate logs, asserting on individual lines misses regressions
+//! in everything else. A snapshot locks down the whole log instead: the normalized logs are
+//! compared line by line against a checked-in file under `tests/snapshots/`.
+//!
+//! ```text
+//! demand_logs_snapshot("deposit", result);
+//!
+//! // Name accounts and ignore compute unit drift
+//! LogSnapshot::new()
+//!     .alias(vault, "vault")
+//!     .mask_compute_units()
+//!     .demand("deposit", result);
+//! ```
+//!
+//! Normalization keeps snapshots stable across runs:
+//! - Pubkeys become aliases. Well-known programs and address book entries get their names,
+//!   any other pubkey (a fresh `Keypair`, say) becomes `<pubkey_N>` in order of appearance
+//! - `Program data:` and `Program return:` payloads become `<N bytes HASH>`
+//! - Compute unit figures become `<cu>`, when masking is enabled
+//!
+//! Set `LITESVM_UPDATE_SNAPSHOTS=1` to write new or changed snapshots instead of failing,
+//! then review and commit the files:
+//!
+//! ```text
+//! LITESVM_UPDATE_SNAPSHOTS=1 cargo test
+//! git diff tests/snapshots/
+//! ```
+
+use std::{collections::HashMap, fs, io, path::Path, path::PathBuf, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use litesvm::types::TransactionResult;
+use regex::Regex;
+use solana_pubkey::Pubkey;
+
+use crate::{address_book::AddressBook, logs_of, or_panic_with, DemandError};
+
+/// Environment variable that, when set to anything but `0`, records snapshots instead of
+/// comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "LITESVM_UPDATE_SNAPSHOTS";
+
+/// Normalization options for a log snapshot.
+///
+/// [`LogSnapshot::new`] names the well-known programs and sysvars of [`AddressBook::new`],
+/// and leaves compute units unmasked. It doesn't read the shared address book, so snapshots
+/// don't depend on what other tests labeled.
+#[derive(Debug, Clone)]
+pub struct LogSnapshot {
+    aliases: AddressBook,
+    mask_compute_units: bool,
+}
+
+impl Default for LogSnapshot {
+    fn default() -> Self {
+        Self {
+            aliases: AddressBook::new(),
+            mask_compute_units: false,
+        }
+    }
+}
+
+impl LogSnapshot {
+    /// Creates the default normalization options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders `pubkey` as `name` in the snapshot.
+    pub fn alias(mut self, pubkey: Pubkey, name: impl Into<String>) -> Self {
+        self.aliases.insert(pubkey, name.into());
+        self
+    }
+
+    /// Renders every pubkey in `address_book` under its label.
+    pub fn address_book(mut self, address_book: &AddressBook) -> Self {
+        self.aliases.merge(address_book);
+        self
+    }
+
+    /// Replaces compute unit figures with `<cu>`, so the snapshot survives CU changes.
+    pub fn mask_compute_units(mut self) -> Self {
+        self.mask_compute_units = true;
+        self
+    }
+
+    /// Returns the transaction's logs as they would be written to a snapshot.
+    pub fn normalize(&self, result: &TransactionResult) -> Vec<String> {
+        self.normalize_lines(logs_of(result))
+    }
+
+    /// Asserts that the normalized logs match the snapshot `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot is missing or differs, unless [`UPDATE_SNAPSHOTS_ENV`] is set,
+    /// in which case the snapshot is written instead.
+    pub fn demand(&self, name: &str, result: TransactionResult) {
+        or_panic_with(self.check(name, &result), &result);
+    }
+
+    /// Checks the normalized logs against the snapshot `name`, without panicking.
+    ///
+    /// Non-panicking version of [`LogSnapshot::demand`].
+    ///
+    /// # Errors
+    ///
+    /// - [`DemandError::SnapshotMissing`] if no snapshot has been recorded
+    /// - [`DemandError::SnapshotMismatch`] with a line diff if the logs changed
+    /// - [`DemandError::SnapshotIo`] if the snapshot couldn't be read or written
+    pub fn check(&self, name: &str, result: &TransactionResult) -> Result<(), DemandError> {
+        compare_snapshot(
+            name,
+            &snapshot_path(name),
+            self.normalize(result),
+            update_requested(),
+        )
+    }
+
+    fn normalize_lines(&self, logs: &[String]) -> Vec<String> {
+        let pubkey = Regex::new(r"\b[1-9A-HJ-NP-Za-km-z]{32,44}\b").unwrap();
+        let consumed = Regex::new(r"consumed \d+ of \d+ compute units").unwrap();
+        let remaining = Regex::new(r"consumption: \d+ units remaining").unwrap();
+
+        let mut generated = HashMap::new();
+
+        logs.iter()
+            .map(|log| {
+                let log = hash_payloads(log);
+
+                let log = pubkey.replace_all(&log, |caps: &regex::Captures| {
+                    let text = &caps[0];
+                    let Ok(key) = Pubkey::from_str(text) else {
+                        return text.to_string();
+                    };
+                    if let Some(name) = self.aliases.label(&key) {
+                        return name.to_string();
+                    }
+                    let next = generated.len() + 1;
+                    generated
+                        .entry(key)
+                        .or_insert_with(|| format!("<pubkey_{}>", next))
+                        .clone()
+                });
+
+                if !self.mask_compute_units {
+                    return log.into_owned();
+                }
+
+                let log = consumed.replace_all(&log, "consumed <cu> of <cu> compute units");
+                remaining
+                    .replace_all(&log, "consumption: <cu> units remaining")
+                    .into_owned()
+            })
+            .collect()
+    }
+}
+
+/// Asserts that a transaction's normalized logs match the checked-in snapshot `name`.
+///
+/// The snapshot lives at `tests/snapshots/<name>.snap` under the crate being tested. Logs
+/// are normalized with the default [`LogSnapshot`] options; build a [`LogSnapshot`] to add
+/// aliases or mask compute units.
+///
+/// # Arguments
+///
+/// * `name` - The snapshot's file name, without the `.snap` extension
+/// * `result` - The result of executing a transaction via [`litesvm::LiteSVM::send_transaction`]
+///
+/// # Panics
+///
+/// Panics if the snapshot is missing, or the normalized logs differ from it. The panic
+/// message shows a line diff. With `LITESVM_UPDATE_SNAPSHOTS=1` the snapshot is written
+/// instead.
+///
+/// # Example
+///
+/// ```text
+/// demand_logs_snapshot("create_ata", result);
+/// ```
+pub fn demand_logs_snapshot(name: &str, result: TransactionResult) {
+    or_panic_with(check_logs_snapshot(name, &result), &result);
+}
+
+/// Checks a transaction's normalized logs against the snapshot `name`, without panicking.
+///
+/// Non-panicking version of [`demand_logs_snapshot`].
+pub fn check_logs_snapshot(name: &str, result: &TransactionResult) -> Result<(), DemandError> {
+    LogSnapshot::default().check(name, result)
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_else(|| ".".into());
+    Path::new(&root)
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name))
+}
+
+fn update_requested() -> bool {
+    std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+fn compare_snapshot(
+    name: &str,
+    path: &Path,
+    actual: Vec<String>,
+    update: bool,
+) -> Result<(), DemandError> {
+    let expected = match fs::read_to_string(path) {
+        Ok(contents) => Some(contents.lines().map(str::to_string).collect::<Vec<_>>()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(snapshot_io(path, e)),
+    };
+
+    if expected.as_ref() == Some(&actual) {
+        return Ok(());
+    }
+
+    if update {
+        return write_snapshot(path, &actual);
+    }
+
+    let name = name.to_string();
+    let path = path.display().to_string();
+
+    Err(match expected {
+        Some(expected) => DemandError::SnapshotMismatch {
+            name,
+            path,
+            expected,
+            actual,
+        },
+        None => DemandError::SnapshotMissing { name, path, actual },
+    })
+}
+
+fn write_snapshot(path: &Path, lines: &[String]) -> Result<(), DemandError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| snapshot_io(path, e))?;
+    }
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    fs::write(path, contents).map_err(|e| snapshot_io(path, e))
+}
+
+fn snapshot_io(path: &Path, error: io::Error) -> DemandError {
+    DemandError::SnapshotIo {
+        path: path.display().to_string(),
+        error: error.to_string(),
+    }
+}
+
+/// Replaces the base64 payloads of `Program data:` and `Program return:` lines with their
+/// length and hash.
+fn hash_payloads(log: &str) -> String {
+    if let Some(data) = log.strip_prefix("Program data: ") {
+        let fields: Vec<_> = data.split(' ').map(hash_payload).collect();
+        return format!("Program data: {}", fields.join(" "));
+    }
+
+    if let Some(rest) = log.strip_prefix("Program return: ") {
+        if let Some((program_id, data)) = rest.split_once(' ') {
+            return format!("Program return: {} {}", program_id, hash_payload(data));
+        }
+    }
+
+    log.to_string()
+}
+
+fn hash_payload(field: &str) -> String {
+    match STANDARD.decode(field) {
+        Ok(bytes) => format!("<{} bytes {:016x}>", bytes.len(), fnv1a(&bytes)),
+        Err(_) => field.to_string(),
+    }
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed, so snapshots stay valid
+/// across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    fn lines(logs: &[&str]) -> Vec<String> {
+        logs.iter().map(|log| log.to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_aliases_pubkeys() {
+        let account = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let logs = lines(&[
+            &format!("Program {} invoke [1]", PROGRAM),
+            &format!("Program log: closing {} to {}", account, other),
+            &format!("Program {} invoke [2]", TOKEN),
+            &format!("Program log: owner {}", account),
+        ]);
+
+        assert_eq!(
+            LogSnapshot::new().normalize_lines(&logs),
+            vec![
+                "Program <pubkey_1> invoke [1]",
+                "Program log: closing <pubkey_2> to <pubkey_3>",
+                "Program spl_token invoke [2]",
+                "Program log: owner <pubkey_2>",
+            ]
+        );
+
+        let named = LogSnapshot::new()
+            .alias(PROGRAM.parse().unwrap(), "vault_program")
+            .address_book(&AddressBook::empty().with(other, "treasury"));
+        assert_eq!(
+            named.normalize_lines(&logs)[..2],
+            [
+                "Program vault_program invoke [1]",
+                "Program log: closing <pubkey_1> to treasury",
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_hashes_payloads_and_masks_compute_units() {
+        let logs = lines(&[
+            "Program data: AQID BAU=",
+            &format!("Program return: {} pQAAAAAAAAA=", TOKEN),
+            &format!("Program {} consumed 1405 of 200000 compute units", TOKEN),
+        ]);
+
+        let unmasked = LogSnapshot::new().normalize_lines(&logs);
+        assert_eq!(
+            unmasked[..2],
+            [
+                format!(
+                    "Program data: <3 bytes {:016x}> <2 bytes {:016x}>",
+                    fnv1a(&[1, 2, 3]),
+                    fnv1a(&[4, 5])
+                ),
+                format!(
+                    "Program return: spl_token <8 bytes {:016x}>",
+                    fnv1a(&165u64.to_le_bytes())
+                ),
+            ]
+        );
+        assert_eq!(
+            unmasked[2],
+            "Program spl_token consumed 1405 of 200000 compute units"
+        );
+
+        let masked = LogSnapshot::new()
+            .mask_compute_units()
+            .normalize_lines(&logs);
+        assert_eq!(
+            masked[2],
+            "Program spl_token consumed <cu> of <cu> compute units"
+        );
+    }
+
+    #[test]
+    fn compare_snapshot_records_only_when_updating() {
+        let path = std::env::temp_dir()
+            .join(format!("litesvm-testing-{}", Pubkey::new_unique()))
+            .join("example.snap");
+        let actual = lines(&["Program log: one", "Program log: two"]);
+
+        assert!(matches!(
+            compare_snapshot("example", &path, actual.clone(), false),
+            Err(DemandError::SnapshotMissing { .. })
+        ));
+
+        compare_snapshot("example", &path, actual.clone(), true).unwrap();
+        compare_snapshot("example", &path, actual.clone(), false).unwrap();
+
+        let changed = lines(&["Program log: one", "Program log: three"]);
+        assert_eq!(
+            compare_snapshot("example", &path, changed.clone(), false),
+            Err(DemandError::SnapshotMismatch {
+                name: "example".to_string(),
+                path: path.display().to_string(),
+                expected: actual,
+                actual: changed.clone(),
+            })
+        );
+
+        compare_snapshot("example", &path, changed.clone(), true).unwrap();
+        compare_snapshot("example", &path, changed, false).unwrap();
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
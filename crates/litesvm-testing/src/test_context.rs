@@ -0,0 +1,257 @@
+//! A test harness around LiteSVM with named, funded actors.
+//!
+//! [`setup_svm_and_fee_payer`](crate::setup_svm_and_fee_payer) hands back a bare runtime and
+//! leaves airdrops, signing and blockhashes to each test. [`TestContext`] does that work:
+//!
+//! ```text
+//! let mut ctx = TestContext::new();
+//! let alice = ctx.actor("alice");  // funded with 10 SOL, labeled "alice"
+//! let bob = ctx.actor("bob");
+//!
+//! ctx.send(&[transfer(&alice.pubkey(), &bob.pubkey(), 1_000)], &[&alice])
+//!     .demand_logs_contain("Program 11111111111111111111111111111111 success");
+//! ```
+//!
+//! The context's payer pays every fee and signs every transaction. [`TestContext::send`]
+//! expires the blockhash after each transaction, so sending the same instructions twice
+//! doesn't fail as already processed.
+//!
+//! Actor labels go into the context's own [`AddressBook`], which [`TestContext::report`]
+//! names accounts from. Assertion failures name accounts from the process-wide book, which
+//! tests running in parallel share, so the context only adds its labels there after
+//! [`TestContext::share_labels`].
+//!
+//! The payer, actors and [`TestContext::keypair`]s are derived from the context's seed and
+//! their labels (see [`seed`](crate::seed)), so pubkeys are the same on every run. A failing
+//! test prints the seed, which `LITESVM_SEED` replays.
+
+use std::collections::HashSet;
+
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{
+    address_book::AddressBook,
+    report::TransactionReport,
+    seed::{seeded_keypair, test_seed, SeedGuard},
+};
+
+/// Lamports in one SOL.
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// The payer's balance in a new [`TestContext`].
+pub const DEFAULT_PAYER_BALANCE: u64 = 100 * LAMPORTS_PER_SOL;
+
+/// The balance [`TestContext::actor`] funds new actors with, unless configured otherwise.
+pub const DEFAULT_ACTOR_BALANCE: u64 = 10 * LAMPORTS_PER_SOL;
+
+/// A LiteSVM runtime with a funded payer, named actors and an address book.
+pub struct TestContext {
+    svm: LiteSVM,
+    payer: Keypair,
+    actors: HashSet<String>,
+    actor_balance: u64,
+    address_book: AddressBook,
+    share_labels: bool,
+    seed: SeedGuard,
+}
+
+impl Default for TestContext {
+    fn default() -> Self {
+        Self::from_svm(LiteSVM::new())
+    }
+}
+
+impl TestContext {
+    /// Creates a context on a fresh LiteSVM, with a payer funded with 100 SOL.
+    ///
+    /// The seed comes from [`test_seed`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a context on a fresh LiteSVM whose keypairs derive from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_svm_with_seed(LiteSVM::new(), seed)
+    }
+
+    /// Creates a context on an existing runtime, e.g. one with programs already deployed.
+    ///
+    /// The payer is funded with 100 SOL, and the seed comes from [`test_seed`].
+    pub fn from_svm(svm: LiteSVM) -> Self {
+        Self::from_svm_with_seed(svm, test_seed())
+    }
+
+    /// Creates a context on an existing runtime whose keypairs derive from `seed`.
+    pub fn from_svm_with_seed(mut svm: LiteSVM, seed: u64) -> Self {
+        let payer = seeded_keypair(seed, "payer");
+        svm.airdrop(&payer.pubkey(), DEFAULT_PAYER_BALANCE)
+            .expect("airdrop failed");
+
+        TestContext {
+            svm,
+            address_book: AddressBook::new().with(payer.pubkey(), "payer"),
+            payer,
+            actors: HashSet::new(),
+            actor_balance: DEFAULT_ACTOR_BALANCE,
+            share_labels: false,
+            seed: SeedGuard::new(seed),
+        }
+    }
+
+    /// Sets the balance that actors created from now on are funded with.
+    pub fn actor_balance(mut self, lamports: u64) -> Self {
+        self.actor_balance = lamports;
+        self
+    }
+
+    /// Sets the payer's balance.
+    pub fn payer_balance(mut self, lamports: u64) -> Self {
+        let mut account = self
+            .svm
+            .get_account(&self.payer.pubkey())
+            .unwrap_or_default();
+        account.lamports = lamports;
+        self.svm
+            .set_account(self.payer.pubkey(), account)
+            .expect("setting the payer's balance failed");
+        self
+    }
+
+    /// Adds the context's labels, now and from now on, to the process-wide address book, so
+    /// assertion failures name them.
+    ///
+    /// The process-wide book is shared by every test, so other tests' failures will use
+    /// these labels too; see [`AddressBook::share`].
+    pub fn share_labels(mut self) -> Self {
+        self.address_book.share();
+        self.share_labels = true;
+        self
+    }
+
+    /// Returns the actor called `name`, creating and funding it on first use.
+    ///
+    /// New actors get the configured actor balance (10 SOL by default) and are labeled
+    /// `name`. Asking for the same name again returns the same keypair.
+    pub fn actor(&mut self, name: &str) -> Keypair {
+        self.actor_with_balance(name, self.actor_balance)
+    }
+
+    /// Returns the actor called `name`, creating it with `lamports` on first use.
+    ///
+    /// An existing actor keeps its balance.
+    pub fn actor_with_balance(&mut self, name: &str, lamports: u64) -> Keypair {
+        let actor = seeded_keypair(self.seed.seed(), name);
+        if self.actors.insert(name.to_string()) {
+            if lamports > 0 {
+                self.svm
+                    .airdrop(&actor.pubkey(), lamports)
+                    .expect("airdrop failed");
+            }
+            self.label(actor.pubkey(), name);
+        }
+        actor
+    }
+
+    /// Returns the keypair for `label`, derived from the context's seed and labeled `label`.
+    ///
+    /// Unlike [`TestContext::actor`], the keypair isn't funded. Use it for accounts a
+    /// transaction creates, such as a mint.
+    pub fn keypair(&mut self, label: &str) -> Keypair {
+        let keypair = seeded_keypair(self.seed.seed(), label);
+        self.label(keypair.pubkey(), label);
+        keypair
+    }
+
+    /// The seed the context's keypairs derive from.
+    pub fn seed(&self) -> u64 {
+        self.seed.seed()
+    }
+
+    /// Labels `pubkey` in the context's address book, and in the shared one after
+    /// [`TestContext::share_labels`].
+    pub fn label(&mut self, pubkey: Pubkey, label: impl Into<String>) {
+        let label = label.into();
+        if self.share_labels {
+            AddressBook::empty().with(pubkey, label.clone()).share();
+        }
+        self.address_book.insert(pubkey, label);
+    }
+
+    /// The keypair that pays for and signs every transaction.
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    /// The labels of the payer, the actors and everything passed to [`TestContext::label`].
+    pub fn address_book(&self) -> &AddressBook {
+        &self.address_book
+    }
+
+    /// Returns `pubkey`'s lamports, or 0 if the account doesn't exist.
+    pub fn balance(&self, pubkey: &Pubkey) -> u64 {
+        self.svm.get_balance(pubkey).unwrap_or_default()
+    }
+
+    /// Builds and signs a transaction of `instructions`, paid for by the payer.
+    ///
+    /// The payer signs in addition to `signers`. The transaction uses the latest blockhash;
+    /// building it doesn't change the runtime, so transactions built earlier stay valid.
+    pub fn transaction(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let mut keypairs = vec![&self.payer];
+        for signer in signers {
+            if keypairs.iter().all(|k| k.pubkey() != signer.pubkey()) {
+                keypairs.push(signer);
+            }
+        }
+
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.svm.latest_blockhash(),
+        )
+    }
+
+    /// Builds, signs and sends a transaction of `instructions`; see
+    /// [`TestContext::transaction`].
+    ///
+    /// The blockhash is expired after sending, so sending the same instructions again isn't
+    /// rejected as a duplicate.
+    // LiteSVM's own result type, so the `demand_*` assertions apply to it directly
+    #[allow(clippy::result_large_err)]
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let tx = self.transaction(instructions, signers);
+        let result = self.svm.send_transaction(tx);
+        self.svm.expire_blockhash();
+        result
+    }
+
+    /// A report of `result` that names accounts from the context's address book.
+    pub fn report<'a>(&self, result: &'a TransactionResult) -> TransactionReport<'a> {
+        TransactionReport::new(result).address_book(&self.address_book)
+    }
+
+    /// The underlying runtime.
+    pub fn svm(&self) -> &LiteSVM {
+        &self.svm
+    }
+
+    /// The underlying runtime, mutably, e.g. to deploy programs or set accounts.
+    pub fn svm_mut(&mut self) -> &mut LiteSVM {
+        &mut self.svm
+    }
+
+    /// Consumes the context, returning the runtime.
+    pub fn into_svm(self) -> LiteSVM {
+        self.svm
+    }
+}
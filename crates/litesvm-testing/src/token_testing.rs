@@ -0,0 +1,964 @@
+//! SPL Token and Token-2022 fixtures and assertions for LiteSVM (`token` feature).
+//!
+//! Setting up a mint and funded token accounts by transaction takes a `create_account`,
+//! an `InitializeMint`, an ATA creation per owner and a `MintTo`, plus the rent math for
+//! each account. These factories write the accounts into the runtime directly instead, with
+//! rent-exempt lamports and the same data the token program would produce:
+//!
+//! ```text
+//! let mint = create_mint(&mut svm, &authority.pubkey(), 6);
+//! let alice_ata = funded_token_account(&mut svm, &alice.pubkey(), &mint, 1_000_000);
+//! let bob_ata = create_ata(&mut svm, &bob.pubkey(), &mint);
+//!
+//! // The accounts behave like ones the token program created
+//! let transfer = spl_token::instruction::transfer(
+//!     &spl_token::ID, &alice_ata, &bob_ata, &alice.pubkey(), &[], 250_000,
+//! )?;
+//! ```
+//!
+//! No transactions are sent, so nobody pays fees or signs. [`mint_to`] raises the mint's
+//! supply along with the account's balance, keeping the two consistent.
+//!
+//! [`MintBuilder`] creates Token-2022 mints with extensions. Token accounts follow their
+//! mint's program, and get the extensions the mint requires of them:
+//!
+//! ```text
+//! let mint = MintBuilder::new(&authority.pubkey(), 6)
+//!     .transfer_fee(100, 1_000_000)   // 1%, at most 1 token
+//!     .create(&mut svm);              // a Token-2022 mint
+//! let alice_ata = funded_token_account(&mut svm, &alice.pubkey(), &mint, 1_000_000);
+//! ```
+//!
+//! The assertions decode SPL Token and Token-2022 accounts alike, and report amounts in UI
+//! units using the mint's decimals:
+//!
+//! ```text
+//! demand_token_balance(&svm, &alice_ata, 750_000);
+//! demand_token_owner(&svm, &alice_ata, &alice.pubkey());
+//! demand_delegate(&svm, &alice_ata, &router, 100_000);
+//! demand_frozen(&svm, &bob_ata);
+//! demand_mint_supply(&svm, &mint, 1_000_000);
+//!
+//! // Expected token balance of account alice.usdc_ata to be 0.75 (750000) but got 0.5 (500000)
+//! ```
+//!
+//! Extension state has assertions of its own: [`demand_transfer_fee`],
+//! [`demand_withheld_fees`], [`demand_transfer_hook`], [`demand_interest_rate`],
+//! [`demand_metadata_pointer`], and [`demand_has_extension`] for the rest.
+
+use litesvm::LiteSVM;
+use solana_clock::Clock;
+use solana_pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodBool};
+use spl_token::solana_program::{program_option::COption, program_pack::Pack};
+use spl_token_2022::{
+    amount_to_ui_amount_string_trimmed,
+    extension::{
+        confidential_transfer::ConfidentialTransferMint,
+        immutable_owner::ImmutableOwner,
+        interest_bearing_mint::InterestBearingConfig,
+        metadata_pointer::MetadataPointer,
+        non_transferable::NonTransferableAccount,
+        pausable::PausableAccount,
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        transfer_hook::{TransferHook, TransferHookAccount},
+        BaseState, BaseStateWithExtensions, BaseStateWithExtensionsMut, Extension, ExtensionType,
+        StateWithExtensions, StateWithExtensionsMut,
+    },
+    state::{Account as TokenAccount, AccountState, Mint},
+};
+
+use crate::{account_builder::AccountBuilder, address_book::address_name, or_panic, DemandError};
+
+/// Creates an initialized SPL Token mint with `authority` as its mint authority and no
+/// freeze authority, and returns its address.
+///
+/// The mint's address is fresh; use [`create_mint_at`] to choose it, or [`MintBuilder`]
+/// for Token-2022 mints and extensions.
+pub fn create_mint(svm: &mut LiteSVM, authority: &Pubkey, decimals: u8) -> Pubkey {
+    MintBuilder::new(authority, decimals).create(svm)
+}
+
+/// Creates an initialized SPL Token mint at `mint`, e.g. a seeded keypair's pubkey.
+pub fn create_mint_at(svm: &mut LiteSVM, mint: &Pubkey, authority: &Pubkey, decimals: u8) {
+    MintBuilder::new(authority, decimals)
+        .address(mint)
+        .create(svm);
+}
+
+/// Builds a mint, optionally under Token-2022 with extensions, and writes it into the
+/// runtime.
+///
+/// A mint with extensions belongs to Token-2022 unless [`MintBuilder::token_program`] says
+/// otherwise. The mint authority also holds every extension's authority: the transfer fee
+/// config and withdraw authorities, the hook, rate, confidential transfer and metadata
+/// pointer authorities.
+///
+/// # Example
+///
+/// ```text
+/// let mint = MintBuilder::new(&authority.pubkey(), 6)
+///     .transfer_fee(50, 5_000)          // 0.5%, at most 0.005 per transfer
+///     .transfer_hook(&hook_program::ID)
+///     .metadata_pointer(&metadata)
+///     .create(&mut svm);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MintBuilder {
+    mint_authority: Pubkey,
+    decimals: u8,
+    address: Option<Pubkey>,
+    token_program: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+    transfer_fee: Option<(u16, u64)>,
+    transfer_hook: Option<Pubkey>,
+    interest_rate: Option<i16>,
+    confidential_transfers: Option<bool>,
+    metadata_pointer: Option<Pubkey>,
+}
+
+impl MintBuilder {
+    /// A mint with `mint_authority`, `decimals`, no freeze authority and no extensions.
+    pub fn new(mint_authority: &Pubkey, decimals: u8) -> Self {
+        MintBuilder {
+            mint_authority: *mint_authority,
+            decimals,
+            address: None,
+            token_program: None,
+            freeze_authority: None,
+            transfer_fee: None,
+            transfer_hook: None,
+            interest_rate: None,
+            confidential_transfers: None,
+            metadata_pointer: None,
+        }
+    }
+
+    /// Creates the mint at `address` instead of a fresh one.
+    pub fn address(mut self, address: &Pubkey) -> Self {
+        self.address = Some(*address);
+        self
+    }
+
+    /// Creates the mint under `token_program`: `spl_token::ID` or `spl_token_2022::ID`.
+    pub fn token_program(mut self, token_program: &Pubkey) -> Self {
+        self.token_program = Some(*token_program);
+        self
+    }
+
+    /// Sets a freeze authority.
+    pub fn freeze_authority(mut self, freeze_authority: &Pubkey) -> Self {
+        self.freeze_authority = Some(*freeze_authority);
+        self
+    }
+
+    /// Adds the transfer fee extension, charging `basis_points` of each transfer, capped
+    /// at `maximum_fee` base units, from the current epoch on.
+    pub fn transfer_fee(mut self, basis_points: u16, maximum_fee: u64) -> Self {
+        self.transfer_fee = Some((basis_points, maximum_fee));
+        self
+    }
+
+    /// Adds the transfer hook extension, calling `program_id` on every transfer.
+    pub fn transfer_hook(mut self, program_id: &Pubkey) -> Self {
+        self.transfer_hook = Some(*program_id);
+        self
+    }
+
+    /// Adds the interest-bearing extension at `rate` basis points a year, starting now.
+    pub fn interest_bearing(mut self, rate: i16) -> Self {
+        self.interest_rate = Some(rate);
+        self
+    }
+
+    /// Adds the confidential transfer extension, without an auditor.
+    pub fn confidential_transfers(mut self, auto_approve_new_accounts: bool) -> Self {
+        self.confidential_transfers = Some(auto_approve_new_accounts);
+        self
+    }
+
+    /// Adds the metadata pointer extension, pointing at `metadata`, often the mint itself.
+    pub fn metadata_pointer(mut self, metadata: &Pubkey) -> Self {
+        self.metadata_pointer = Some(*metadata);
+        self
+    }
+
+    fn extension_types(&self) -> Vec<ExtensionType> {
+        [
+            (
+                self.transfer_fee.is_some(),
+                ExtensionType::TransferFeeConfig,
+            ),
+            (self.transfer_hook.is_some(), ExtensionType::TransferHook),
+            (
+                self.interest_rate.is_some(),
+                ExtensionType::InterestBearingConfig,
+            ),
+            (
+                self.confidential_transfers.is_some(),
+                ExtensionType::ConfidentialTransferMint,
+            ),
+            (
+                self.metadata_pointer.is_some(),
+                ExtensionType::MetadataPointer,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, extension)| enabled.then_some(extension))
+        .collect()
+    }
+
+    /// Writes the mint with rent-exempt lamports, and returns its address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the token program isn't SPL Token or Token-2022, or if it's SPL Token
+    /// and extensions were added.
+    pub fn create(self, svm: &mut LiteSVM) -> Pubkey {
+        let extension_types = self.extension_types();
+        let token_program = self.token_program.unwrap_or(if extension_types.is_empty() {
+            spl_token::ID
+        } else {
+            spl_token_2022::ID
+        });
+        assert_token_program(&token_program);
+        assert!(
+            token_program == spl_token_2022::ID || extension_types.is_empty(),
+            "mint extensions {:?} need Token-2022, not SPL Token",
+            extension_types
+        );
+
+        let authority = OptionalNonZeroPubkey(self.mint_authority);
+        let clock = svm.get_sysvar::<Clock>();
+        let mint = Mint {
+            mint_authority: COption::Some(self.mint_authority),
+            supply: 0,
+            decimals: self.decimals,
+            is_initialized: true,
+            freeze_authority: self.freeze_authority.into(),
+        };
+
+        let mut data = vec![0; state_len::<Mint>(&token_program, &extension_types)];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data)
+            .expect("sizing the mint failed");
+        state.base = mint;
+        state.pack_base();
+        if !extension_types.is_empty() {
+            state.init_account_type().expect("tagging the mint failed");
+        }
+
+        if let Some((basis_points, maximum_fee)) = self.transfer_fee {
+            let fee = TransferFee {
+                epoch: clock.epoch.into(),
+                maximum_fee: maximum_fee.into(),
+                transfer_fee_basis_points: basis_points.into(),
+            };
+            let config = init_extension::<Mint, TransferFeeConfig>(&mut state);
+            config.transfer_fee_config_authority = authority;
+            config.withdraw_withheld_authority = authority;
+            config.older_transfer_fee = fee;
+            config.newer_transfer_fee = fee;
+        }
+        if let Some(program_id) = self.transfer_hook {
+            let hook = init_extension::<Mint, TransferHook>(&mut state);
+            hook.authority = authority;
+            hook.program_id = OptionalNonZeroPubkey(program_id);
+        }
+        if let Some(rate) = self.interest_rate {
+            let config = init_extension::<Mint, InterestBearingConfig>(&mut state);
+            config.rate_authority = authority;
+            config.initialization_timestamp = clock.unix_timestamp.into();
+            config.last_update_timestamp = clock.unix_timestamp.into();
+            config.pre_update_average_rate = rate.into();
+            config.current_rate = rate.into();
+        }
+        if let Some(auto_approve_new_accounts) = self.confidential_transfers {
+            let config = init_extension::<Mint, ConfidentialTransferMint>(&mut state);
+            config.authority = authority;
+            config.auto_approve_new_accounts = PodBool::from(auto_approve_new_accounts);
+        }
+        if let Some(metadata) = self.metadata_pointer {
+            let pointer = init_extension::<Mint, MetadataPointer>(&mut state);
+            pointer.authority = authority;
+            pointer.metadata_address = OptionalNonZeroPubkey(metadata);
+        }
+
+        let address = self.address.unwrap_or_else(Pubkey::new_unique);
+        write_state(svm, &address, &token_program, data);
+        address
+    }
+}
+
+/// Creates `owner`'s empty associated token account for `mint`, and returns its address.
+///
+/// The account belongs to the mint's token program. Under Token-2022 it carries the
+/// extensions the mint requires of its accounts, such as `TransferFeeAmount`, plus
+/// `ImmutableOwner`, as the associated token account program would create it.
+///
+/// # Panics
+///
+/// Panics if `mint` isn't an SPL Token or Token-2022 mint.
+pub fn create_ata(svm: &mut LiteSVM, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let (token_program, mint_data) = get_state(svm, mint, "mint");
+    let mint_extensions = StateWithExtensions::<Mint>::unpack(&mint_data)
+        .and_then(|state| state.get_extension_types())
+        .unwrap_or_else(|e| panic!("mint {} failed to unpack: {}", mint, e));
+
+    let mut extension_types = Vec::new();
+    if token_program == spl_token_2022::ID {
+        extension_types = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+        if !extension_types.contains(&ExtensionType::ImmutableOwner) {
+            extension_types.push(ExtensionType::ImmutableOwner);
+        }
+    }
+
+    let account = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount: 0,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = vec![0; state_len::<TokenAccount>(&token_program, &extension_types)];
+    let mut state = StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data)
+        .expect("sizing the token account failed");
+    state.base = account;
+    state.pack_base();
+    if !extension_types.is_empty() {
+        state
+            .init_account_type()
+            .expect("tagging the token account failed");
+    }
+    for extension_type in &extension_types {
+        match extension_type {
+            ExtensionType::TransferFeeAmount => {
+                init_extension::<TokenAccount, TransferFeeAmount>(&mut state);
+            }
+            ExtensionType::TransferHookAccount => {
+                init_extension::<TokenAccount, TransferHookAccount>(&mut state);
+            }
+            ExtensionType::NonTransferableAccount => {
+                init_extension::<TokenAccount, NonTransferableAccount>(&mut state);
+            }
+            ExtensionType::PausableAccount => {
+                init_extension::<TokenAccount, PausableAccount>(&mut state);
+            }
+            ExtensionType::ImmutableOwner => {
+                init_extension::<TokenAccount, ImmutableOwner>(&mut state);
+            }
+            other => panic!("token account extension {:?} isn't supported", other),
+        }
+    }
+
+    let ata = get_associated_token_address_with_program_id(owner, mint, &token_program);
+    write_state(svm, &ata, &token_program, data);
+    ata
+}
+
+/// Mints `amount` tokens of `mint` into `token_account`, raising the mint's supply.
+///
+/// # Panics
+///
+/// Panics if either account is missing or not a token account of the same token program,
+/// if `token_account` holds another mint, or if the supply or balance would overflow.
+pub fn mint_to(svm: &mut LiteSVM, mint: &Pubkey, token_account: &Pubkey, amount: u64) {
+    let (mint_program, mut mint_data) = get_state(svm, mint, "mint");
+    let (account_program, mut account_data) = get_state(svm, token_account, "token account");
+    assert_eq!(
+        mint_program, account_program,
+        "mint {} and token account {} belong to different token programs",
+        mint, token_account
+    );
+
+    let mut mint_state = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)
+        .unwrap_or_else(|e| panic!("mint {} failed to unpack: {}", mint, e));
+    let mut account_state = StateWithExtensionsMut::<TokenAccount>::unpack(&mut account_data)
+        .unwrap_or_else(|e| panic!("token account {} failed to unpack: {}", token_account, e));
+    assert_eq!(
+        account_state.base.mint, *mint,
+        "token account {} holds mint {}, not {}",
+        token_account, account_state.base.mint, mint
+    );
+
+    mint_state.base.supply = mint_state
+        .base
+        .supply
+        .checked_add(amount)
+        .expect("mint supply overflowed");
+    account_state.base.amount = account_state
+        .base
+        .amount
+        .checked_add(amount)
+        .expect("token account balance overflowed");
+    mint_state.pack_base();
+    account_state.pack_base();
+
+    write_state(svm, mint, &mint_program, mint_data);
+    write_state(svm, token_account, &account_program, account_data);
+}
+
+/// Creates `owner`'s associated token account for `mint` holding `amount` tokens, and
+/// returns its address.
+///
+/// One call for [`create_ata`] followed by [`mint_to`].
+pub fn funded_token_account(
+    svm: &mut LiteSVM,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let ata = create_ata(svm, owner, mint);
+    mint_to(svm, mint, &ata, amount);
+    ata
+}
+
+/// Returns the balance of `token_account`, an SPL Token or Token-2022 account.
+///
+/// # Panics
+///
+/// Panics if the account is missing or not a token account.
+pub fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+    let (_, data) = get_state(svm, token_account, "token account");
+    StateWithExtensions::<TokenAccount>::unpack(&data)
+        .unwrap_or_else(|e| panic!("token account {} failed to unpack: {}", token_account, e))
+        .base
+        .amount
+}
+
+/// Asserts that a token account holds exactly `amount` base units.
+///
+/// # Panics
+///
+/// Panics if the account isn't a token account or its balance differs.
+pub fn demand_token_balance(svm: &LiteSVM, token_account: &Pubkey, amount: u64) {
+    or_panic(check_token_balance(svm, token_account, amount));
+}
+
+/// Checks that a token account holds exactly `amount` base units, without panicking.
+///
+/// Non-panicking version of [`demand_token_balance`].
+pub fn check_token_balance(
+    svm: &LiteSVM,
+    token_account: &Pubkey,
+    amount: u64,
+) -> Result<(), DemandError> {
+    let state = unpack_token_account(svm, token_account)?;
+    let decimals = mint_decimals(svm, &state.mint);
+
+    check_amount(
+        token_account,
+        "token balance",
+        amount,
+        state.amount,
+        decimals,
+    )
+}
+
+/// Asserts that a token account is owned (in the token program's sense) by `owner`.
+///
+/// # Panics
+///
+/// Panics if the account isn't a token account or has a different owner.
+pub fn demand_token_owner(svm: &LiteSVM, token_account: &Pubkey, owner: &Pubkey) {
+    or_panic(check_token_owner(svm, token_account, owner));
+}
+
+/// Checks that a token account is owned by `owner`, without panicking.
+///
+/// Non-panicking version of [`demand_token_owner`].
+pub fn check_token_owner(
+    svm: &LiteSVM,
+    token_account: &Pubkey,
+    owner: &Pubkey,
+) -> Result<(), DemandError> {
+    let state = unpack_token_account(svm, token_account)?;
+
+    check_pubkey(token_account, "token owner", owner, Some(state.owner))
+}
+
+/// Asserts that a token account has approved `delegate` for exactly `amount` base units.
+///
+/// # Panics
+///
+/// Panics if the account isn't a token account, has another delegate or none, or the
+/// delegated amount differs.
+pub fn demand_delegate(svm: &LiteSVM, token_account: &Pubkey, delegate: &Pubkey, amount: u64) {
+    or_panic(check_delegate(svm, token_account, delegate, amount));
+}
+
+/// Checks a token account's delegate and delegated amount, without panicking.
+///
+/// Non-panicking version of [`demand_delegate`].
+pub fn check_delegate(
+    svm: &LiteSVM,
+    token_account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Result<(), DemandError> {
+    let state = unpack_token_account(svm, token_account)?;
+    check_pubkey(token_account, "delegate", delegate, state.delegate.into())?;
+
+    let decimals = mint_decimals(svm, &state.mint);
+    check_amount(
+        token_account,
+        "delegated amount",
+        amount,
+        state.delegated_amount,
+        decimals,
+    )
+}
+
+/// Asserts that a token account is frozen.
+///
+/// # Panics
+///
+/// Panics if the account isn't a token account or isn't frozen.
+pub fn demand_frozen(svm: &LiteSVM, token_account: &Pubkey) {
+    or_panic(check_frozen(svm, token_account));
+}
+
+/// Checks that a token account is frozen, without panicking.
+///
+/// Non-panicking version of [`demand_frozen`].
+pub fn check_frozen(svm: &LiteSVM, token_account: &Pubkey) -> Result<(), DemandError> {
+    let state = unpack_token_account(svm, token_account)?;
+    let observed = match state.state {
+        spl_token_2022::state::AccountState::Frozen => "frozen",
+        spl_token_2022::state::AccountState::Initialized => "initialized",
+        spl_token_2022::state::AccountState::Uninitialized => "uninitialized",
+    };
+
+    check_field(token_account, "state", "frozen", observed)
+}
+
+/// Asserts that a mint's supply is exactly `supply` base units.
+///
+/// # Panics
+///
+/// Panics if the account isn't a mint or its supply differs.
+pub fn demand_mint_supply(svm: &LiteSVM, mint: &Pubkey, supply: u64) {
+    or_panic(check_mint_supply(svm, mint, supply));
+}
+
+/// Checks that a mint's supply is exactly `supply` base units, without panicking.
+///
+/// Non-panicking version of [`demand_mint_supply`].
+pub fn check_mint_supply(svm: &LiteSVM, mint: &Pubkey, supply: u64) -> Result<(), DemandError> {
+    let state = unpack_token_state::<spl_token_2022::state::Mint>(svm, mint, "mint")?;
+
+    check_amount(mint, "supply", supply, state.supply, Some(state.decimals))
+}
+
+/// Asserts that a mint or token account has the `extension` Token-2022 extension.
+///
+/// # Panics
+///
+/// Panics if the account isn't a mint or token account, or lacks the extension.
+pub fn demand_has_extension(svm: &LiteSVM, pubkey: &Pubkey, extension: ExtensionType) {
+    or_panic(check_has_extension(svm, pubkey, extension));
+}
+
+/// Checks that a mint or token account has the `extension` extension, without panicking.
+///
+/// Non-panicking version of [`demand_has_extension`].
+pub fn check_has_extension(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+    extension: ExtensionType,
+) -> Result<(), DemandError> {
+    let present = if is_token_account(svm, pubkey) {
+        with_token_state(
+            svm,
+            pubkey,
+            "token account",
+            extension_types::<TokenAccount>,
+        )?
+    } else {
+        with_token_state(svm, pubkey, "mint", extension_types::<Mint>)?
+    };
+    if present.contains(&extension) {
+        return Ok(());
+    }
+
+    Err(extension_mismatch(pubkey, extension, &present))
+}
+
+/// Asserts that a mint charges `basis_points` of each transfer, capped at `maximum_fee`
+/// base units, in the current epoch.
+///
+/// # Panics
+///
+/// Panics if the account isn't a mint with the transfer fee extension, or the fee differs.
+pub fn demand_transfer_fee(svm: &LiteSVM, mint: &Pubkey, basis_points: u16, maximum_fee: u64) {
+    or_panic(check_transfer_fee(svm, mint, basis_points, maximum_fee));
+}
+
+/// Checks a mint's transfer fee for the current epoch, without panicking.
+///
+/// Non-panicking version of [`demand_transfer_fee`].
+pub fn check_transfer_fee(
+    svm: &LiteSVM,
+    mint: &Pubkey,
+    basis_points: u16,
+    maximum_fee: u64,
+) -> Result<(), DemandError> {
+    let config = unpack_extension::<Mint, TransferFeeConfig>(svm, mint, "mint")?;
+    let fee = config.get_epoch_fee(svm.get_sysvar::<Clock>().epoch);
+    check_field(
+        mint,
+        "transfer fee basis points",
+        basis_points.to_string(),
+        u16::from(fee.transfer_fee_basis_points).to_string(),
+    )?;
+
+    let decimals = mint_decimals(svm, mint);
+    check_amount(
+        mint,
+        "maximum transfer fee",
+        maximum_fee,
+        fee.maximum_fee.into(),
+        decimals,
+    )
+}
+
+/// Asserts that a token account or mint holds exactly `amount` base units of withheld
+/// transfer fees.
+///
+/// A token account's fees are withheld from the transfers it received; a mint's are the
+/// fees harvested to it.
+///
+/// # Panics
+///
+/// Panics if the account has no transfer fee extension or the withheld amount differs.
+pub fn demand_withheld_fees(svm: &LiteSVM, pubkey: &Pubkey, amount: u64) {
+    or_panic(check_withheld_fees(svm, pubkey, amount));
+}
+
+/// Checks the transfer fees withheld in a token account or mint, without panicking.
+///
+/// Non-panicking version of [`demand_withheld_fees`].
+pub fn check_withheld_fees(svm: &LiteSVM, pubkey: &Pubkey, amount: u64) -> Result<(), DemandError> {
+    let (withheld, mint) = if is_token_account(svm, pubkey) {
+        let fees =
+            unpack_extension::<TokenAccount, TransferFeeAmount>(svm, pubkey, "token account")?;
+        let account = unpack_token_account(svm, pubkey)?;
+        (fees.withheld_amount, account.mint)
+    } else {
+        let config = unpack_extension::<Mint, TransferFeeConfig>(svm, pubkey, "mint")?;
+        (config.withheld_amount, *pubkey)
+    };
+    let decimals = mint_decimals(svm, &mint);
+
+    check_amount(pubkey, "withheld fees", amount, withheld.into(), decimals)
+}
+
+/// Asserts that a mint's transfer hook calls `program_id`.
+///
+/// # Panics
+///
+/// Panics if the account isn't a mint with the transfer hook extension, or the hook
+/// calls another program or none.
+pub fn demand_transfer_hook(svm: &LiteSVM, mint: &Pubkey, program_id: &Pubkey) {
+    or_panic(check_transfer_hook(svm, mint, program_id));
+}
+
+/// Checks a mint's transfer hook program, without panicking.
+///
+/// Non-panicking version of [`demand_transfer_hook`].
+pub fn check_transfer_hook(
+    svm: &LiteSVM,
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<(), DemandError> {
+    let hook = unpack_extension::<Mint, TransferHook>(svm, mint, "mint")?;
+
+    check_pubkey(
+        mint,
+        "transfer hook program",
+        program_id,
+        hook.program_id.into(),
+    )
+}
+
+/// Asserts that a mint accrues interest at `rate` basis points a year.
+///
+/// # Panics
+///
+/// Panics if the account isn't an interest-bearing mint, or its current rate differs.
+pub fn demand_interest_rate(svm: &LiteSVM, mint: &Pubkey, rate: i16) {
+    or_panic(check_interest_rate(svm, mint, rate));
+}
+
+/// Checks an interest-bearing mint's current rate, without panicking.
+///
+/// Non-panicking version of [`demand_interest_rate`].
+pub fn check_interest_rate(svm: &LiteSVM, mint: &Pubkey, rate: i16) -> Result<(), DemandError> {
+    let config = unpack_extension::<Mint, InterestBearingConfig>(svm, mint, "mint")?;
+
+    check_field(
+        mint,
+        "interest rate (basis points)",
+        rate.to_string(),
+        i16::from(config.current_rate).to_string(),
+    )
+}
+
+/// Asserts that a mint's metadata pointer points at `metadata`.
+///
+/// # Panics
+///
+/// Panics if the account isn't a mint with the metadata pointer extension, or it points
+/// elsewhere or nowhere.
+pub fn demand_metadata_pointer(svm: &LiteSVM, mint: &Pubkey, metadata: &Pubkey) {
+    or_panic(check_metadata_pointer(svm, mint, metadata));
+}
+
+/// Checks a mint's metadata pointer, without panicking.
+///
+/// Non-panicking version of [`demand_metadata_pointer`].
+pub fn check_metadata_pointer(
+    svm: &LiteSVM,
+    mint: &Pubkey,
+    metadata: &Pubkey,
+) -> Result<(), DemandError> {
+    let pointer = unpack_extension::<Mint, MetadataPointer>(svm, mint, "mint")?;
+
+    check_pubkey(
+        mint,
+        "metadata pointer",
+        metadata,
+        pointer.metadata_address.into(),
+    )
+}
+
+fn extension_mismatch(
+    pubkey: &Pubkey,
+    expected: ExtensionType,
+    present: &[ExtensionType],
+) -> DemandError {
+    let present: Vec<String> = present.iter().map(|e| format!("{:?}", e)).collect();
+    DemandError::AccountMismatch {
+        pubkey: *pubkey,
+        field: "extensions",
+        expected: format!("{:?}", expected),
+        observed: if present.is_empty() {
+            "none".to_string()
+        } else {
+            present.join(", ")
+        },
+    }
+}
+
+fn extension_types<S: BaseState + Pack>(
+    state: &StateWithExtensions<S>,
+) -> Result<Vec<ExtensionType>, DemandError> {
+    Ok(state.get_extension_types().unwrap_or_default())
+}
+
+/// Whether `pubkey` holds a token account, as opposed to a mint or anything else.
+fn is_token_account(svm: &LiteSVM, pubkey: &Pubkey) -> bool {
+    svm.get_account(pubkey)
+        .is_some_and(|account| StateWithExtensions::<TokenAccount>::unpack(&account.data).is_ok())
+}
+
+/// Renders a base-unit amount with its UI amount, e.g. `1.5 (1500000)`.
+fn ui_amount(amount: u64, decimals: Option<u8>) -> String {
+    match decimals {
+        Some(decimals) => format!(
+            "{} ({})",
+            amount_to_ui_amount_string_trimmed(amount, decimals),
+            amount
+        ),
+        None => amount.to_string(),
+    }
+}
+
+fn check_amount(
+    pubkey: &Pubkey,
+    field: &'static str,
+    expected: u64,
+    observed: u64,
+    decimals: Option<u8>,
+) -> Result<(), DemandError> {
+    if expected == observed {
+        return Ok(());
+    }
+
+    Err(DemandError::AccountMismatch {
+        pubkey: *pubkey,
+        field,
+        expected: ui_amount(expected, decimals),
+        observed: ui_amount(observed, decimals),
+    })
+}
+
+fn check_field(
+    pubkey: &Pubkey,
+    field: &'static str,
+    expected: impl Into<String>,
+    observed: impl Into<String>,
+) -> Result<(), DemandError> {
+    let (expected, observed) = (expected.into(), observed.into());
+    if expected == observed {
+        return Ok(());
+    }
+
+    Err(DemandError::AccountMismatch {
+        pubkey: *pubkey,
+        field,
+        expected,
+        observed,
+    })
+}
+
+/// Compares pubkeys, not their labels: labels aren't unique.
+fn check_pubkey(
+    pubkey: &Pubkey,
+    field: &'static str,
+    expected: &Pubkey,
+    observed: Option<Pubkey>,
+) -> Result<(), DemandError> {
+    if observed == Some(*expected) {
+        return Ok(());
+    }
+
+    Err(DemandError::AccountMismatch {
+        pubkey: *pubkey,
+        field,
+        expected: address_name(expected),
+        observed: observed
+            .map(|observed| address_name(&observed))
+            .unwrap_or_else(|| "none".to_string()),
+    })
+}
+
+/// The mint's decimals, if it exists and decodes.
+fn mint_decimals(svm: &LiteSVM, mint: &Pubkey) -> Option<u8> {
+    unpack_token_state::<spl_token_2022::state::Mint>(svm, mint, "mint")
+        .ok()
+        .map(|mint| mint.decimals)
+}
+
+fn unpack_token_account(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+) -> Result<spl_token_2022::state::Account, DemandError> {
+    unpack_token_state(svm, pubkey, "token account")
+}
+
+/// Decodes the base state of an SPL Token or Token-2022 account, ignoring any extensions.
+fn unpack_token_state<S: BaseState + Pack + Clone>(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+    expected: &'static str,
+) -> Result<S, DemandError> {
+    with_token_state(svm, pubkey, expected, |state: &StateWithExtensions<S>| {
+        Ok(state.base.clone())
+    })
+}
+
+/// Decodes the `V` extension of an SPL Token or Token-2022 account.
+///
+/// A missing extension is reported as a mismatch of the account's extension list.
+fn unpack_extension<S, V>(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+    expected: &'static str,
+) -> Result<V, DemandError>
+where
+    S: BaseState + Pack,
+    V: Extension + bytemuck::Pod,
+{
+    with_token_state(svm, pubkey, expected, |state: &StateWithExtensions<S>| {
+        if let Ok(extension) = state.get_extension::<V>() {
+            return Ok(*extension);
+        }
+
+        let present = state.get_extension_types().unwrap_or_default();
+        Err(extension_mismatch(pubkey, V::TYPE, &present))
+    })
+}
+
+/// Runs `f` on the decoded state of an SPL Token or Token-2022 account.
+fn with_token_state<S: BaseState + Pack, R>(
+    svm: &LiteSVM,
+    pubkey: &Pubkey,
+    expected: &'static str,
+    f: impl FnOnce(&StateWithExtensions<S>) -> Result<R, DemandError>,
+) -> Result<R, DemandError> {
+    let account = svm
+        .get_account(pubkey)
+        .ok_or(DemandError::AccountNotFound { pubkey: *pubkey })?;
+    let not_token_state = |reason: String| DemandError::NotTokenState {
+        pubkey: *pubkey,
+        expected,
+        reason,
+    };
+
+    if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+        return Err(not_token_state(format!(
+            "it is owned by {}",
+            address_name(&account.owner)
+        )));
+    }
+    let state = StateWithExtensions::<S>::unpack(&account.data)
+        .map_err(|e| not_token_state(format!("its data does not unpack: {}", e)))?;
+    f(&state)
+}
+
+/// Returns the owning token program and data of `pubkey`.
+fn get_state(svm: &LiteSVM, pubkey: &Pubkey, kind: &str) -> (Pubkey, Vec<u8>) {
+    let account = svm
+        .get_account(pubkey)
+        .unwrap_or_else(|| panic!("{} {} does not exist", kind, pubkey));
+    assert!(
+        account.owner == spl_token::ID || account.owner == spl_token_2022::ID,
+        "{} {} is owned by {}, not SPL Token or Token-2022",
+        kind,
+        pubkey,
+        account.owner
+    );
+    (account.owner, account.data)
+}
+
+/// Writes `data` to `pubkey`, owned by `token_program`, with rent-exempt lamports.
+fn write_state(svm: &mut LiteSVM, pubkey: &Pubkey, token_program: &Pubkey, data: Vec<u8>) {
+    AccountBuilder::new()
+        .owner(token_program)
+        .data(data)
+        .write(svm, pubkey);
+}
+
+fn assert_token_program(token_program: &Pubkey) {
+    assert!(
+        *token_program == spl_token::ID || *token_program == spl_token_2022::ID,
+        "{} is neither SPL Token nor Token-2022",
+        token_program
+    );
+}
+
+/// The data length of a `S` with `extension_types` under `token_program`.
+fn state_len<S: BaseState + Pack>(
+    token_program: &Pubkey,
+    extension_types: &[ExtensionType],
+) -> usize {
+    if *token_program == spl_token::ID {
+        return S::LEN;
+    }
+    ExtensionType::try_calculate_account_len::<S>(extension_types)
+        .expect("sizing the extensions failed")
+}
+
+fn init_extension<'a, S, V>(state: &'a mut StateWithExtensionsMut<S>) -> &'a mut V
+where
+    S: BaseState + Pack,
+    V: Extension + bytemuck::Pod + Default,
+{
+    state
+        .init_extension::<V>(true)
+        .unwrap_or_else(|e| panic!("initializing {:?} failed: {}", V::TYPE, e))
+}
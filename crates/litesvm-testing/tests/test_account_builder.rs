@@ -0,0 +1,144 @@
+//! # Account Builder
+//!
+//! `AccountBuilder` writes accounts into the runtime without transactions. These tests
+//! check the defaults (rent-exempt lamports, system owner), each way of setting the data,
+//! and that the token program accepts the packed token shortcuts.
+
+use litesvm_testing::{account_testing::*, prelude::*};
+
+// `#[account]` types are owned by the crate's program id
+#[cfg(feature = "anchor")]
+anchor_lang::declare_id!("Vau1t11111111111111111111111111111111111111");
+
+#[test]
+fn lamports_default_to_rent_exempt() {
+    let mut ctx = TestContext::new();
+    let config = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+
+    AccountBuilder::new()
+        .owner(&program_id)
+        .data(vec![1, 2, 3, 4])
+        .write(ctx.svm_mut(), &config);
+
+    let rent_exempt = ctx.svm().minimum_balance_for_rent_exemption(4);
+    demand_lamports(ctx.svm(), &config, rent_exempt);
+    demand_owner(ctx.svm(), &config, &program_id);
+    demand_data_eq(ctx.svm(), &config, &[1, 2, 3, 4]);
+    demand_executable(ctx.svm(), &config, false);
+}
+
+#[test]
+fn explicit_fields_and_encodings() {
+    let mut ctx = TestContext::new();
+    let (wallet, config, zeroed, packed, program) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let admin = Pubkey::new_unique();
+
+    AccountBuilder::new()
+        .lamports(5_000_000_000)
+        .write(ctx.svm_mut(), &wallet);
+    assert_eq!(ctx.balance(&wallet), 5_000_000_000);
+    demand_owner(ctx.svm(), &wallet, &system_program::ID);
+
+    AccountBuilder::new()
+        .borsh(&(admin.to_bytes(), 30u16))
+        .write(ctx.svm_mut(), &config);
+    demand_data_len(ctx.svm(), &config, 34);
+    demand_data_range_eq(ctx.svm(), &config, 32, &30u16.to_le_bytes());
+
+    AccountBuilder::new()
+        .space(64)
+        .write(ctx.svm_mut(), &zeroed);
+    demand_data_eq(ctx.svm(), &zeroed, &[0; 64]);
+
+    let mint = spl_token::state::Mint {
+        decimals: 3,
+        is_initialized: true,
+        ..Default::default()
+    };
+    AccountBuilder::new()
+        .owner(&spl_token::ID)
+        .pack(&mint)
+        .write(ctx.svm_mut(), &packed);
+    demand_data_len(ctx.svm(), &packed, 82);
+
+    // A copy of the SPL Token program, loaded from its ELF
+    let token_program = ctx.svm().get_account(&spl_token::ID).unwrap();
+    AccountBuilder::new()
+        .owner(&token_program.owner)
+        .data(token_program.data)
+        .executable(true)
+        .write(ctx.svm_mut(), &program);
+    demand_executable(ctx.svm(), &program, true);
+}
+
+#[cfg(feature = "token")]
+#[test]
+fn token_shortcuts_work_with_the_token_program() {
+    use litesvm_testing::token_testing::{demand_mint_supply, demand_token_balance};
+
+    let mut ctx = TestContext::new();
+    let alice = ctx.actor("alice");
+    let (mint, alice_ata, bob_ata) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    AccountBuilder::mint(&Pubkey::new_unique(), 1_000, 2).write(ctx.svm_mut(), &mint);
+    AccountBuilder::token_account(&mint, &alice.pubkey(), 1_000).write(ctx.svm_mut(), &alice_ata);
+    AccountBuilder::token_account(&mint, &Pubkey::new_unique(), 0).write(ctx.svm_mut(), &bob_ata);
+
+    let transfer = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &alice_ata,
+        &bob_ata,
+        &alice.pubkey(),
+        &[],
+        400,
+    )
+    .unwrap();
+    ctx.send(&[transfer], &[&alice])
+        .demand_logs_contain("Instruction: Transfer");
+
+    demand_token_balance(ctx.svm(), &alice_ata, 600);
+    demand_token_balance(ctx.svm(), &bob_ata, 400);
+    demand_mint_supply(ctx.svm(), &mint, 1_000);
+}
+
+#[cfg(feature = "anchor")]
+mod anchor {
+    use anchor_lang::{prelude::*, Discriminator};
+    use litesvm_testing::{account_testing::*, prelude::*};
+
+    #[account]
+    pub struct Vault {
+        pub authority: Pubkey,
+        pub balance: u64,
+    }
+
+    #[test]
+    fn anchor_accounts_get_their_discriminator_and_owner() {
+        let mut ctx = TestContext::new();
+        let vault = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        AccountBuilder::new()
+            .anchor(&Vault {
+                authority,
+                balance: 7,
+            })
+            .write(ctx.svm_mut(), &vault);
+
+        demand_owner(ctx.svm(), &vault, &crate::ID);
+        demand_data_len(ctx.svm(), &vault, 8 + 32 + 8);
+        demand_data_range_eq(ctx.svm(), &vault, 0, Vault::DISCRIMINATOR);
+        demand_data_range_eq(ctx.svm(), &vault, 8, authority.as_ref());
+    }
+}
@@ -0,0 +1,102 @@
+//! # Account Fixtures
+//!
+//! `tests/fixtures/accounts/` holds accounts in the format `solana account --output json`
+//! prints: a base64 SPL Token mint and a base58 system account. These tests load them,
+//! use the mint in a real token instruction, and export accounts back to identical JSON.
+
+use std::{fs, path::PathBuf};
+
+use litesvm_testing::{account_testing::*, prelude::*};
+
+use litesvm::LiteSVM;
+use spl_token::instruction::{set_authority, AuthorityType};
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/accounts")
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("litesvm-testing-{}-{}", name, std::process::id()))
+}
+
+#[test]
+fn fixtures_load_into_the_runtime() {
+    // The mint authority is the seed-0 keypair labeled "authority"
+    let mut ctx = TestContext::with_seed(0);
+    let authority = ctx.keypair("authority");
+
+    let loaded = load_account_fixture_dir(ctx.svm_mut(), fixtures_dir());
+    let [treasury, usdc]: [Pubkey; 2] = loaded.try_into().unwrap();
+
+    demand_lamports(ctx.svm(), &treasury, 2_500_000_000);
+    demand_owner(ctx.svm(), &treasury, &system_program::ID);
+    demand_data_eq(ctx.svm(), &treasury, b"treasury v1");
+    demand_owner(ctx.svm(), &usdc, &spl_token::ID);
+    demand_data_len(ctx.svm(), &usdc, 82);
+
+    let revoke = set_authority(
+        &spl_token::ID,
+        &usdc,
+        None,
+        AuthorityType::MintTokens,
+        &authority.pubkey(),
+        &[],
+    )
+    .unwrap();
+    ctx.send(&[revoke], &[&authority])
+        .demand_logs_contain("Instruction: SetAuthority");
+}
+
+#[test]
+fn exports_match_the_cli_format() {
+    let mut svm = LiteSVM::new();
+    let paths = [
+        fixtures_dir().join("usdc_mint.json"),
+        fixtures_dir().join("treasury.json"),
+    ];
+    let pubkeys = load_account_fixtures(&mut svm, &paths);
+
+    let dir = scratch_dir("export");
+    let exported = export_account_fixtures(&svm, &pubkeys, &dir);
+
+    // Base64 data exports byte for byte; base58 data comes back as base64
+    assert_eq!(
+        fs::read_to_string(&exported[0]).unwrap(),
+        fs::read_to_string(&paths[0]).unwrap()
+    );
+    let treasury = AccountFixture::read(&exported[1]);
+    assert_eq!(treasury, AccountFixture::read(&paths[1]));
+    assert!(fs::read_to_string(&exported[1])
+        .unwrap()
+        .contains("\"base64\""));
+
+    let mut replay = LiteSVM::new();
+    assert_eq!(load_account_fixture_dir(&mut replay, &dir).len(), 2);
+    assert_eq!(
+        replay.get_account(&pubkeys[0]),
+        svm.get_account(&pubkeys[0])
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn legacy_and_unsupported_encodings() {
+    let legacy = r#"{
+        "pubkey": "7JSHmXiFd8L7oQy1Bomu5rjJFZCGc56ULn2KK2wMmQD3",
+        "account": {
+            "lamports": 1,
+            "data": "2",
+            "owner": "11111111111111111111111111111111",
+            "executable": false,
+            "rentEpoch": 0
+        }
+    }"#;
+    assert_eq!(AccountFixture::from_json(legacy).unwrap().account.data, [1]);
+
+    let zstd = legacy.replace(r#""2""#, r#"["KLUv/QBYCQAAAQ==", "base64+zstd"]"#);
+    let error = AccountFixture::from_json(&zstd).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("unsupported data encoding \"base64+zstd\""));
+}
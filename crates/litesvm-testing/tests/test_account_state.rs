@@ -0,0 +1,165 @@
+//! # Account State Assertions
+//!
+//! Transactions are only half the story: these tests check what they leave behind using
+//! `litesvm_testing::account_testing`. Accounts are created and drained through the system
+//! program, then inspected straight from the `LiteSVM` instance.
+
+use litesvm_testing::{account_testing::*, prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_interface::instruction::{create_account, transfer},
+    solana_transaction::Transaction,
+};
+
+const DATA_LEN: usize = 16;
+
+/// Creates a `DATA_LEN` byte account owned by `owner`, funded with exactly its rent exemption.
+fn setup_program_owned_account(owner: &Pubkey) -> (LiteSVM, Keypair, Keypair) {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let account = Keypair::new();
+    let lamports = svm.minimum_balance_for_rent_exemption(DATA_LEN);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_account(
+            &fee_payer.pubkey(),
+            &account.pubkey(),
+            lamports,
+            DATA_LEN as u64,
+            owner,
+        )],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &account],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).expect("create_account failed");
+
+    (svm, fee_payer, account)
+}
+
+#[test]
+fn demand_created_account_state() {
+    let owner = Pubkey::new_unique();
+    let (svm, _, account) = setup_program_owned_account(&owner);
+    let pubkey = account.pubkey();
+
+    demand_account_exists(&svm, &pubkey);
+    demand_lamports(
+        &svm,
+        &pubkey,
+        svm.minimum_balance_for_rent_exemption(DATA_LEN),
+    );
+    demand_owner(&svm, &pubkey, &owner);
+    demand_data_len(&svm, &pubkey, DATA_LEN);
+    demand_executable(&svm, &pubkey, false);
+    demand_data_eq(&svm, &pubkey, &[0; DATA_LEN]);
+    demand_data_range_eq(&svm, &pubkey, 4, &[0; 8]);
+}
+
+#[test]
+fn demand_drained_account_closed() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let account = Keypair::new();
+    svm.airdrop(&account.pubkey(), 1_000_000).unwrap();
+    demand_account_exists(&svm, &account.pubkey());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer(&account.pubkey(), &fee_payer.pubkey(), 1_000_000)],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &account],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).expect("transfer failed");
+
+    demand_account_closed(&svm, &account.pubkey());
+    demand_account_closed(&svm, &Pubkey::new_unique());
+}
+
+#[test]
+fn check_reports_account_mismatches() {
+    let owner = Pubkey::new_unique();
+    let (svm, fee_payer, account) = setup_program_owned_account(&owner);
+    let pubkey = account.pubkey();
+
+    assert_eq!(
+        check_owner(&svm, &pubkey, &system_program::ID),
+        Err(DemandError::AccountMismatch {
+            pubkey,
+            field: "owner",
+            expected: system_program::ID.to_string(),
+            observed: owner.to_string(),
+        })
+    );
+
+    assert_eq!(
+        check_data_len(&svm, &pubkey, 8),
+        Err(DemandError::AccountMismatch {
+            pubkey,
+            field: "data length",
+            expected: "8".to_string(),
+            observed: DATA_LEN.to_string(),
+        })
+    );
+
+    assert!(matches!(
+        check_account_closed(&svm, &fee_payer.pubkey()),
+        Err(DemandError::AccountNotClosed { .. })
+    ));
+
+    let missing = Pubkey::new_unique();
+    assert_eq!(
+        check_lamports(&svm, &missing, 0),
+        Err(DemandError::AccountNotFound { pubkey: missing })
+    );
+}
+
+#[test]
+fn check_reports_data_range_mismatch() {
+    let (svm, _, account) = setup_program_owned_account(&Pubkey::new_unique());
+    let pubkey = account.pubkey();
+
+    let err = check_data_range_eq(&svm, &pubkey, 12, &[0, 0, 7, 0, 9]).unwrap_err();
+
+    assert_eq!(
+        err,
+        DemandError::DataMismatch {
+            pubkey,
+            offset: 12,
+            expected: vec![0, 0, 7, 0, 9],
+            observed: vec![0, 0, 0, 0],
+        }
+    );
+}
+
+#[test]
+fn check_data_range_past_the_end() {
+    let (svm, _, account) = setup_program_owned_account(&Pubkey::new_unique());
+    let pubkey = account.pubkey();
+
+    // The range's end would overflow `usize`
+    assert_eq!(
+        check_data_range_eq(&svm, &pubkey, usize::MAX, &[1]),
+        Err(DemandError::DataMismatch {
+            pubkey,
+            offset: usize::MAX,
+            expected: vec![1],
+            observed: vec![],
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "000000  expected: 00 00 00 01")]
+fn demand_data_eq_panics_with_hex_diff() {
+    let (svm, _, account) = setup_program_owned_account(&Pubkey::new_unique());
+
+    let mut expected = [0; DATA_LEN];
+    expected[3] = 1;
+
+    demand_data_eq(&svm, &account.pubkey(), &expected);
+}
@@ -0,0 +1,120 @@
+//! # Address Book
+//!
+//! One `AddressBook` labels pubkeys for every piece of output. These tests label a token
+//! setup once and read the labels back from assertion failures, the transaction report and
+//! a log snapshot's normalization.
+//!
+//! Scenario: the fee payer creates a mint and an ATA for `alice`. The ATA is labeled
+//! `alice.usdc_ata`, under alice's own label.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer, snapshot_testing::LogSnapshot};
+
+use {
+    litesvm::LiteSVM, //
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    },
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+/// A stand-in program id; PDAs derive the same whether or not the program is deployed.
+const VAULT_PROGRAM: Pubkey = Pubkey::new_from_array([7; 32]);
+
+struct UsdcScenario {
+    svm: LiteSVM,
+    tx: Transaction,
+    alice_usdc: Pubkey,
+    book: AddressBook,
+}
+
+fn setup_usdc_scenario() -> UsdcScenario {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let usdc = Keypair::new();
+    let alice = Pubkey::new_unique();
+    let alice_usdc = get_associated_token_address(&alice, &usdc.pubkey());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &usdc.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &usdc.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &alice,
+                &usdc.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &usdc],
+        svm.latest_blockhash(),
+    );
+
+    let mut book = AddressBook::new()
+        .with(usdc.pubkey(), "usdc")
+        .with(alice, "alice");
+    book.insert_under(&alice, "usdc_ata", alice_usdc);
+
+    UsdcScenario {
+        svm,
+        tx,
+        alice_usdc,
+        book,
+    }
+}
+
+#[test]
+fn report_and_snapshot_use_the_labels() {
+    let UsdcScenario {
+        mut svm, tx, book, ..
+    } = setup_usdc_scenario();
+    let result = svm.send_transaction(tx.clone());
+
+    let report = TransactionReport::new(&result)
+        .transaction(&tx)
+        .address_book(&book)
+        .to_string();
+    assert!(report.contains("accounts [alice.usdc_ata, usdc]"));
+
+    let logs = LogSnapshot::new().address_book(&book).normalize(&result);
+    assert!(logs.contains(&"Program spl_token invoke [2]".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Expected account alice.usdc_ata to be closed but it holds")]
+fn shared_labels_appear_in_assertion_failures() {
+    let UsdcScenario {
+        mut svm,
+        tx,
+        alice_usdc,
+        book,
+    } = setup_usdc_scenario();
+    book.share();
+
+    svm.send_transaction(tx).demand_logs_contain("Initialize");
+    demand_account_closed(&svm, &alice_usdc);
+}
+
+#[test]
+fn pda_labels_follow_their_seeds() {
+    let UsdcScenario {
+        alice_usdc, book, ..
+    } = setup_usdc_scenario();
+    let mut book = book.with(VAULT_PROGRAM, "vault_program");
+
+    let vault = book.pda(&VAULT_PROGRAM, &[b"vault", alice_usdc.as_ref()]);
+
+    assert_eq!(book.name(&vault), "vault_program[vault, alice.usdc_ata]");
+    assert_eq!(
+        book.pubkey("vault_program[vault, alice.usdc_ata]"),
+        Some(vault)
+    );
+    assert_eq!(AddressBook::new().name(&vault), vault.to_string());
+}
@@ -0,0 +1,351 @@
+//! # Balance Change Assertions
+//!
+//! `BalanceTracker` snapshots balances before a transaction and asserts exact signed deltas
+//! afterwards. These tests move lamports with the system program and tokens with the SPL
+//! Token program, and show how the fee is kept out of the fee payer's expectation and
+//! checked against what the payer was charged.
+
+use litesvm_testing::{balance_testing::*, prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::ed25519_program,
+    solana_signer::Signer,
+    solana_system_interface::instruction::{create_account, transfer},
+    solana_transaction::Transaction,
+    spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    },
+    spl_token::{
+        instruction::{initialize_mint, mint_to},
+        solana_program::program_pack::Pack,
+    },
+};
+
+/// Creates a mint and funded ATAs for a sender (holding `amount` tokens) and a recipient.
+fn setup_token_accounts(amount: u64) -> (LiteSVM, Keypair, Keypair, Pubkey, Pubkey) {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let mint = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let sender_ata = get_associated_token_address(&sender.pubkey(), &mint.pubkey());
+    let recipient_ata = get_associated_token_address(&recipient, &mint.pubkey());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &sender.pubkey(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &recipient,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+            mint_to(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &sender_ata,
+                &fee_payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).expect("token setup failed");
+
+    (svm, fee_payer, sender, sender_ata, recipient_ata)
+}
+
+#[test]
+fn demand_lamport_deltas_with_fee_separated() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let recipient = Pubkey::new_unique();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer(&fee_payer.pubkey(), &recipient, 1_000_000)],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+
+    let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[recipient]);
+    assert_eq!(tracker.fee(), Some(LAMPORTS_PER_SIGNATURE));
+
+    svm.send_transaction(tx).expect("transfer failed");
+
+    // The fee payer's expectation excludes the 5000 lamport fee
+    tracker.demand_deltas(
+        &svm,
+        &[
+            BalanceDelta::Lamports(fee_payer.pubkey(), -1_000_000),
+            BalanceDelta::Lamports(recipient, 1_000_000),
+        ],
+    );
+}
+
+#[test]
+fn demand_token_deltas() {
+    let (mut svm, fee_payer, sender, sender_ata, recipient_ata) = setup_token_accounts(1_000);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::transfer(
+            &spl_token::ID,
+            &sender_ata,
+            &recipient_ata,
+            &sender.pubkey(),
+            &[],
+            250,
+        )
+        .unwrap()],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &sender],
+        svm.latest_blockhash(),
+    );
+
+    // Two signatures: the fee payer and the token owner
+    let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[sender_ata, recipient_ata]);
+    assert_eq!(tracker.fee(), Some(2 * LAMPORTS_PER_SIGNATURE));
+
+    svm.send_transaction(tx).expect("token transfer failed");
+
+    // Lamports of the token accounts are untouched, so only token deltas are listed
+    tracker.demand_deltas(
+        &svm,
+        &[
+            BalanceDelta::Tokens(sender_ata, -250),
+            BalanceDelta::Tokens(recipient_ata, 250),
+        ],
+    );
+}
+
+#[test]
+fn expected_fee_is_compared_with_the_charged_fee() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let recipient = Pubkey::new_unique();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer(&fee_payer.pubkey(), &recipient, 1_000)],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[recipient]).with_fee(7_000);
+    svm.send_transaction(tx).expect("transfer failed");
+
+    let expected = [
+        BalanceDelta::Lamports(fee_payer.pubkey(), -1_000),
+        BalanceDelta::Lamports(recipient, 1_000),
+    ];
+    let rows = tracker.rows(&svm, &expected).unwrap();
+
+    // The transfer matches; only the fee row differs
+    assert_eq!(
+        rows[..2],
+        [
+            BalanceRow {
+                pubkey: fee_payer.pubkey(),
+                asset: "lamports",
+                expected: -1_000,
+                actual: -1_000,
+            },
+            BalanceRow {
+                pubkey: fee_payer.pubkey(),
+                asset: "fee",
+                expected: -7_000,
+                actual: -5_000,
+            },
+        ]
+    );
+    assert!(tracker.check_deltas(&svm, &expected).is_err());
+}
+
+#[test]
+fn precompile_signatures_are_charged() {
+    let fee_payer = Pubkey::new_unique();
+    let verify_two = Instruction::new_with_bytes(ed25519_program::ID, &[2, 0], vec![]);
+    let tx = Transaction::new_unsigned(solana_message::Message::new(
+        &[verify_two],
+        Some(&fee_payer),
+    ));
+
+    assert_eq!(transaction_fee(&tx), 3 * LAMPORTS_PER_SIGNATURE);
+}
+
+/// A Token-2022 account holding `amount`, with an `ImmutableOwner` extension.
+fn token_2022_account(amount: u64) -> Vec<u8> {
+    let account = spl_token::state::Account {
+        mint: Pubkey::new_from_array([1; 32]),
+        owner: Pubkey::new_from_array([2; 32]),
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    account.pack_into_slice(&mut data);
+
+    // Account type, then the extension's type (7) and zero length
+    data.extend_from_slice(&[2, 7, 0, 0, 0]);
+    data
+}
+
+#[test]
+fn token_2022_accounts_with_extensions_are_tracked() {
+    let mut svm = LiteSVM::new();
+    let token_2022 = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        .parse()
+        .unwrap();
+    let ata = Pubkey::new_unique();
+    let write = |svm: &mut LiteSVM, amount| {
+        AccountBuilder::new()
+            .owner(&token_2022)
+            .data(token_2022_account(amount))
+            .lamports(1_000_000)
+            .write(svm, &ata)
+    };
+
+    write(&mut svm, 500);
+    let tracker = BalanceTracker::snapshot(&svm, &[ata]);
+    write(&mut svm, 800);
+
+    tracker.demand_deltas(&svm, &[BalanceDelta::Tokens(ata, 300)]);
+}
+
+#[cfg(feature = "token")]
+#[test]
+fn token_2022_deltas() {
+    use litesvm_testing::token_testing::{create_ata, funded_token_account, MintBuilder};
+
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let sender = Keypair::new();
+    let mint = MintBuilder::new(&Pubkey::new_unique(), 0)
+        .transfer_fee(0, 0)
+        .create(&mut svm);
+    let sender_ata = funded_token_account(&mut svm, &sender.pubkey(), &mint, 1_000);
+    let recipient_ata = create_ata(&mut svm, &Pubkey::new_unique(), &mint);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &sender_ata,
+            &mint,
+            &recipient_ata,
+            &sender.pubkey(),
+            &[],
+            300,
+            0,
+        )
+        .unwrap()],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &sender],
+        svm.latest_blockhash(),
+    );
+    let tracker = BalanceTracker::snapshot_for(&svm, &tx, &[sender_ata, recipient_ata]);
+    svm.send_transaction(tx).expect("token transfer failed");
+
+    // The accounts carry extensions, so they're longer than SPL Token accounts
+    tracker.demand_deltas(
+        &svm,
+        &[
+            BalanceDelta::Tokens(sender_ata, -300),
+            BalanceDelta::Tokens(recipient_ata, 300),
+        ],
+    );
+}
+
+#[test]
+fn check_deltas_reports_every_tracked_balance() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let recipient = Pubkey::new_unique();
+    let bystander = Pubkey::new_unique();
+    svm.airdrop(&bystander, 1_000_000).unwrap();
+
+    let tracker = BalanceTracker::snapshot(&svm, &[recipient, bystander]);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer(&fee_payer.pubkey(), &recipient, 700)],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).expect("transfer failed");
+
+    assert_eq!(
+        tracker.check_deltas(&svm, &[BalanceDelta::Lamports(recipient, 500)]),
+        Err(DemandError::BalanceMismatch {
+            rows: vec![
+                BalanceRow {
+                    pubkey: recipient,
+                    asset: "lamports",
+                    expected: 500,
+                    actual: 700,
+                },
+                BalanceRow {
+                    pubkey: bystander,
+                    asset: "lamports",
+                    expected: 0,
+                    actual: 0,
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "tokens              +100             +99  <-")]
+fn demand_deltas_panics_with_table() {
+    let (mut svm, fee_payer, sender, sender_ata, recipient_ata) = setup_token_accounts(1_000);
+
+    let tracker = BalanceTracker::snapshot(&svm, &[recipient_ata]);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::transfer(
+            &spl_token::ID,
+            &sender_ata,
+            &recipient_ata,
+            &sender.pubkey(),
+            &[],
+            99,
+        )
+        .unwrap()],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &sender],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).expect("token transfer failed");
+
+    tracker.demand_deltas(&svm, &[BalanceDelta::Tokens(recipient_ata, 100)]);
+}
+
+#[test]
+fn check_deltas_rejects_untracked_accounts() {
+    let (svm, _) = setup_svm_and_fee_payer();
+    let tracked = Pubkey::new_unique();
+    let untracked = Pubkey::new_unique();
+
+    let tracker = BalanceTracker::snapshot(&svm, &[tracked]);
+
+    assert_eq!(
+        tracker.check_deltas(&svm, &[BalanceDelta::Lamports(untracked, 1)]),
+        Err(DemandError::UntrackedAccount { pubkey: untracked })
+    );
+}
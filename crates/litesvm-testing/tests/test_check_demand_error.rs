@@ -0,0 +1,153 @@
+//! # Non-panicking Checks
+//!
+//! Every `demand_*` assertion has a `check_*` counterpart that returns
+//! `Result<(), DemandError>` instead of panicking. These tests use the same insufficient
+//! funds scenario as `test_system_error_insufficient_funds.rs` and inspect the structured
+//! failures that would otherwise become panic messages.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_instruction::error::InstructionError,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::error::SystemError,
+    solana_transaction::Transaction,
+    solana_transaction_error::TransactionError,
+};
+
+/// Compute budget at index 0, a transfer that fails with
+/// `SystemError::ResultWithNegativeLamports` at index 1.
+fn setup_insufficient_funds_scenario() -> (LiteSVM, Transaction) {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let poor_account = Keypair::new();
+    svm.airdrop(&poor_account.pubkey(), 1000)
+        .expect("airdrop failed");
+
+    let transfer_ix = solana_system_interface::instruction::transfer(
+        &poor_account.pubkey(),
+        &Keypair::new().pubkey(),
+        500_000,
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(4000),
+            transfer_ix,
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &poor_account],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn check_passes_when_expectation_holds() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    let result = svm.send_transaction(tx);
+
+    assert_eq!(
+        check_system_error(SystemError::ResultWithNegativeLamports, &result),
+        Ok(())
+    );
+    assert_eq!(
+        check_system_error_at_index(1, SystemError::ResultWithNegativeLamports, &result),
+        Ok(())
+    );
+    assert_eq!(check_logs_contain("insufficient lamports", &result), Ok(()));
+}
+
+#[test]
+fn check_reports_wrong_index() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    let result = svm.send_transaction(tx);
+
+    let err = check_system_error_at_index(0, SystemError::ResultWithNegativeLamports, &result)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        DemandError::WrongIndex {
+            expected: SystemError::ResultWithNegativeLamports.to_string(),
+            expected_index: 0,
+            observed_index: 1,
+        }
+    );
+}
+
+#[test]
+fn check_reports_wrong_error() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    let result = svm.send_transaction(tx);
+
+    let err = check_instruction_error_at_index(1, InstructionError::InvalidArgument, &result)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        DemandError::WrongError {
+            expected: InstructionError::InvalidArgument.to_string(),
+            observed: "system::ResultWithNegativeLamports (0x1)".to_string(),
+            index: Some(1),
+        }
+    );
+}
+
+#[test]
+fn check_reports_unexpected_success() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let transfer_ix = solana_system_interface::instruction::transfer(
+        &fee_payer.pubkey(),
+        &Keypair::new().pubkey(),
+        1_000_000,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer_ix],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+
+    let err = check_transaction_error(TransactionError::AlreadyProcessed, &result).unwrap_err();
+
+    assert!(matches!(
+        err,
+        DemandError::UnexpectedSuccess {
+            expected_index: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn check_reports_log_not_found() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    let result = svm.send_transaction(tx);
+
+    let Err(DemandError::LogNotFound {
+        expected,
+        index,
+        logs,
+    }) = check_logs_contain("Hello from nowhere", &result)
+    else {
+        panic!("expected LogNotFound");
+    };
+
+    assert_eq!(expected, "Hello from nowhere");
+    assert_eq!(index, None);
+    assert_eq!(&logs, &result.unwrap_err().meta.logs);
+}
+
+#[test]
+#[should_panic(expected = "at index 0 but got error at index 1")]
+fn demand_panics_with_check_message() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    svm.send_transaction(tx)
+        .demand_system_error_at_index(0, SystemError::ResultWithNegativeLamports);
+}
@@ -0,0 +1,131 @@
+//! # Compute Unit Assertions
+//!
+//! Guards against CU regressions with `litesvm_testing::cu_testing`. A system transfer is
+//! cheap and deterministic, which makes it a good baseline; an SPL token transfer shows the
+//! per-instruction variant reading a BPF program's `consumed X of Y compute units` log.
+
+use litesvm_testing::{cu_testing::*, prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_keypair::Keypair,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_interface::{error::SystemError, instruction::transfer},
+    solana_transaction::Transaction,
+};
+
+/// Sets up a compute budget instruction followed by a system transfer of `lamports`.
+fn setup_transfer_scenario(lamports: u64) -> (LiteSVM, Transaction) {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let sender = Keypair::new();
+    svm.airdrop(&sender.pubkey(), 1_000_000).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(10_000),
+            transfer(&sender.pubkey(), &Pubkey::new_unique(), lamports),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &sender],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+fn send_transfer(lamports: u64) -> u64 {
+    let (mut svm, tx) = setup_transfer_scenario(lamports);
+    compute_units(&svm.send_transaction(tx))
+}
+
+#[test]
+fn demand_cu_direct() {
+    // Identical transactions on fresh SVMs consume identical compute units
+    let units = send_transfer(1_000);
+
+    let (mut svm, tx) = setup_transfer_scenario(1_000);
+    demand_cu_at_most(units, svm.send_transaction(tx));
+
+    let (mut svm, tx) = setup_transfer_scenario(1_000);
+    demand_cu_between(units, units + 100, svm.send_transaction(tx));
+
+    let (mut svm, tx) = setup_transfer_scenario(1_000);
+    demand_cu_within_pct_of(units, 1.0, svm.send_transaction(tx));
+}
+
+#[test]
+fn demand_cu_fluent_on_failed_transaction() {
+    // Compute units are reported for failed transactions too
+    let units = send_transfer(5_000_000);
+    assert!(units > 0);
+
+    let (mut svm, tx) = setup_transfer_scenario(5_000_000);
+    svm.send_transaction(tx).demand_cu_at_most(units);
+
+    let (mut svm, tx) = setup_transfer_scenario(5_000_000);
+    svm.send_transaction(tx)
+        .demand_cu_between_and(units, units)
+        .demand_cu_within_pct_of_and(units, 0.0)
+        .demand_system_error_at_index_and(1, SystemError::ResultWithNegativeLamports)
+        .accept();
+}
+
+#[test]
+#[should_panic(expected = "Expected compute units at most 1 but transaction consumed")]
+fn demand_cu_at_most_panics() {
+    let (mut svm, tx) = setup_transfer_scenario(1_000);
+    svm.send_transaction(tx).demand_cu_at_most(1);
+}
+
+#[test]
+fn check_cu_at_most_at_index_needs_compute_unit_log() {
+    // Builtins like the compute budget and system programs don't log consumed compute units
+    let (mut svm, tx) = setup_transfer_scenario(1_000);
+
+    assert_eq!(
+        check_cu_at_most_at_index(1, 10_000, &svm.send_transaction(tx)),
+        Err(DemandError::ComputeUnitsUnavailable {
+            index: 1,
+            instructions: 2,
+        })
+    );
+}
+
+#[test]
+fn demand_cu_at_most_at_index_for_token_program() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    // Initializing a mint that doesn't exist fails inside the SPL Token program, which
+    // still logs the compute units it consumed
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &Pubkey::new_unique(),
+                &fee_payer.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+
+    let units = instruction_compute_units(&result);
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0], None);
+    let token_units = units[1].expect("token program logs consumed compute units");
+    let total_units = compute_units(&result);
+
+    result
+        .demand_cu_at_most_at_index_and(1, token_units)
+        .demand_cu_at_most_and(total_units)
+        .accept();
+}
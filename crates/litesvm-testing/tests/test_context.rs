@@ -0,0 +1,141 @@
+//! # Test Context
+//!
+//! `TestContext` replaces the airdrop, signing and blockhash boilerplate of
+//! `setup_svm_and_fee_payer`. These tests move SOL between named actors and check that the
+//! context funds them, labels them and keeps repeated transactions distinct, and that its
+//! keypairs are the same for the same seed.
+
+use litesvm_testing::{
+    prelude::*,
+    seed::seeded_keypair,
+    test_context::{DEFAULT_ACTOR_BALANCE, LAMPORTS_PER_SOL},
+};
+
+use solana_system_interface::instruction::transfer;
+
+#[test]
+fn actors_are_funded_and_reused() {
+    let mut ctx = TestContext::new();
+    let alice = ctx.actor("alice");
+    let whale = ctx.actor_with_balance("whale", 1_000 * LAMPORTS_PER_SOL);
+
+    assert_eq!(ctx.balance(&alice.pubkey()), DEFAULT_ACTOR_BALANCE);
+    assert_eq!(ctx.balance(&whale.pubkey()), 1_000 * LAMPORTS_PER_SOL);
+    assert_eq!(ctx.actor("alice").pubkey(), alice.pubkey());
+    assert_eq!(ctx.address_book().name(&alice.pubkey()), "alice");
+}
+
+#[test]
+fn balances_are_configurable() {
+    let mut ctx = TestContext::new()
+        .actor_balance(LAMPORTS_PER_SOL)
+        .payer_balance(5 * LAMPORTS_PER_SOL);
+    let alice = ctx.actor("alice");
+
+    assert_eq!(ctx.balance(&alice.pubkey()), LAMPORTS_PER_SOL);
+    assert_eq!(ctx.balance(&ctx.payer().pubkey()), 5 * LAMPORTS_PER_SOL);
+}
+
+#[test]
+fn send_signs_pays_and_refreshes_the_blockhash() {
+    let mut ctx = TestContext::new();
+    let alice = ctx.actor("alice");
+    let bob = ctx.actor("bob");
+    let payer_balance = ctx.balance(&ctx.payer().pubkey());
+
+    // The same instruction twice: a stale blockhash would reject the second as a duplicate
+    let ix = transfer(&alice.pubkey(), &bob.pubkey(), LAMPORTS_PER_SOL);
+    for _ in 0..2 {
+        ctx.send(std::slice::from_ref(&ix), &[&alice])
+            .demand_logs_contain("success");
+    }
+
+    assert_eq!(
+        ctx.balance(&alice.pubkey()),
+        DEFAULT_ACTOR_BALANCE - 2 * LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        ctx.balance(&bob.pubkey()),
+        DEFAULT_ACTOR_BALANCE + 2 * LAMPORTS_PER_SOL
+    );
+
+    // The payer paid both fees; alice only signed
+    assert_eq!(
+        ctx.balance(&ctx.payer().pubkey()),
+        payer_balance - 2 * 10_000
+    );
+}
+
+#[test]
+fn building_transactions_keeps_earlier_ones_valid() {
+    let mut ctx = TestContext::new();
+    let alice = ctx.actor("alice");
+    let bob = ctx.actor("bob");
+
+    let first = ctx.transaction(&[transfer(&alice.pubkey(), &bob.pubkey(), 1)], &[&alice]);
+    let second = ctx.transaction(&[transfer(&alice.pubkey(), &bob.pubkey(), 2)], &[&alice]);
+
+    ctx.svm_mut()
+        .send_transaction(first)
+        .demand_logs_contain("success");
+    ctx.svm_mut()
+        .send_transaction(second)
+        .demand_logs_contain("success");
+    assert_eq!(ctx.balance(&bob.pubkey()), DEFAULT_ACTOR_BALANCE + 3);
+}
+
+#[test]
+fn inner_svm_is_available() {
+    let mut ctx = TestContext::new();
+    let carol = Pubkey::new_unique();
+
+    ctx.svm_mut().airdrop(&carol, 42).unwrap();
+    ctx.label(carol, "carol");
+
+    assert_eq!(ctx.svm().get_balance(&carol), Some(42));
+    assert_eq!(ctx.into_svm().get_balance(&carol), Some(42));
+}
+
+#[test]
+#[should_panic(expected = "Expected lamports of account alice to be 1 but got")]
+fn assertion_failures_name_actors() {
+    let mut ctx = TestContext::new().share_labels();
+    let alice = ctx.actor("alice");
+
+    demand_lamports(ctx.svm(), &alice.pubkey(), 1);
+}
+
+#[test]
+fn labels_are_only_shared_on_request() {
+    let mut ctx = TestContext::with_seed(7);
+    let dave = ctx.actor("dave").pubkey();
+
+    assert_eq!(ctx.address_book().name(&dave), "dave");
+    assert_eq!(address_name(&dave), dave.to_string());
+
+    let mut ctx = ctx.share_labels();
+    let erin = ctx.actor("erin").pubkey();
+    assert_eq!(address_name(&dave), "dave");
+    assert_eq!(address_name(&erin), "erin");
+}
+
+#[test]
+fn keypairs_are_reproducible_from_the_seed() {
+    let mut ctx = TestContext::with_seed(42);
+    let mint = ctx.keypair("mint");
+    let alice = ctx.actor("alice");
+
+    let mut replay = TestContext::with_seed(42);
+    assert_eq!(replay.seed(), 42);
+    assert_eq!(replay.keypair("mint").pubkey(), mint.pubkey());
+    assert_eq!(replay.actor("alice").pubkey(), alice.pubkey());
+    assert_eq!(replay.payer().pubkey(), ctx.payer().pubkey());
+    assert_eq!(mint.pubkey(), seeded_keypair(42, "mint").pubkey());
+
+    // Keypairs are labeled but not funded
+    assert_eq!(ctx.address_book().name(&mint.pubkey()), "mint");
+    assert_eq!(ctx.balance(&mint.pubkey()), 0);
+
+    let other = TestContext::with_seed(43);
+    assert_ne!(other.payer().pubkey(), ctx.payer().pubkey());
+}
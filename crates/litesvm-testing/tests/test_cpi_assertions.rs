@@ -0,0 +1,169 @@
+//! # CPI Assertions
+//!
+//! Creating an associated token account is a compact CPI workout. The ATA program, invoked
+//! as top-level instruction `[2]`, makes four CPIs:
+//!
+//! - SPL Token `GetAccountDataSize` (discriminator 21)
+//! - system program `CreateAccount`
+//! - SPL Token `InitializeImmutableOwner` (22)
+//! - SPL Token `InitializeAccount3` (18), on `[ata, mint]` with the owner as data
+//!
+//! Instructions `[0]` (system) and `[1]` (SPL Token `InitializeMint2`) make none.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    },
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+struct AtaScenario {
+    svm: LiteSVM,
+    tx: Transaction,
+    mint: Pubkey,
+    owner: Pubkey,
+}
+
+fn setup_mint_and_ata_scenario() -> AtaScenario {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let owner = Pubkey::new_unique();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &owner,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    AtaScenario {
+        svm,
+        tx,
+        mint: mint.pubkey(),
+        owner,
+    }
+}
+
+#[test]
+fn demand_cpis_direct() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    demand_cpi_invoked(&spl_token::ID, result.clone());
+    demand_cpi_invoked(&system_program::ID, result.clone());
+    demand_cpi_count(1, 0, result.clone());
+    demand_cpi_count(2, 4, result.clone());
+    demand_cpi_sequence(
+        &[
+            spl_token::ID,
+            system_program::ID,
+            spl_token::ID,
+            spl_token::ID,
+        ],
+        result,
+    );
+}
+
+#[test]
+fn demand_cpis_chained() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_cpi_invoked_and(&spl_token::ID)
+        .demand_cpi_count_and(0, 0)
+        .demand_cpi_and(&CpiMatcher::new(spl_token::ID).data_prefix(&[22]))
+        .accept();
+}
+
+#[test]
+fn demand_cpi_matches_data_and_accounts() {
+    let AtaScenario {
+        mut svm,
+        tx,
+        mint,
+        owner,
+    } = setup_mint_and_ata_scenario();
+    let ata = get_associated_token_address(&owner, &mint);
+
+    let initialize_account = CpiMatcher::new(spl_token::ID)
+        .data_prefix(&[&[18][..], owner.as_ref()].concat())
+        .accounts(&tx, &[ata, mint]);
+    let result = svm.send_transaction(tx.clone());
+
+    result.clone().demand_cpi(&initialize_account);
+
+    let observed = &cpis(&result).unwrap()[3];
+    assert_eq!(observed.instruction_index, 2);
+    assert_eq!(observed.depth, 2);
+    assert!(initialize_account.matches(observed));
+
+    // Same instruction on the wrong accounts, and a different instruction entirely
+    let wrong_accounts = CpiMatcher::new(spl_token::ID)
+        .data_prefix(&[18])
+        .accounts(&tx, &[mint, ata]);
+    let transfer_checked = CpiMatcher::new(spl_token::ID).data_prefix(&[12]);
+
+    for matcher in [wrong_accounts, transfer_checked] {
+        assert!(matches!(
+            check_cpi(&matcher, &result),
+            Err(DemandError::CpiNotFound { cpis, .. }) if cpis.len() == 4
+        ));
+    }
+}
+
+#[test]
+fn check_cpis_report_what_was_invoked() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    assert!(matches!(
+        check_cpi_invoked(&spl_associated_token_account::ID, &result),
+        Err(DemandError::CpiNotFound { .. })
+    ));
+    assert!(matches!(
+        check_cpi_count(2, 3, &result),
+        Err(DemandError::WrongCpiCount { expected_count: 3, cpis, .. }) if cpis.len() == 4
+    ));
+    assert_eq!(
+        check_cpi_sequence(&[spl_token::ID], &result),
+        Err(DemandError::WrongCpiSequence {
+            expected: vec![spl_token::ID],
+            observed: vec![
+                spl_token::ID,
+                system_program::ID,
+                spl_token::ID,
+                spl_token::ID
+            ],
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected 1 CPIs from instruction 1 but found 0")]
+fn demand_cpi_count_panics_with_observed_cpis() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx).demand_cpi_count(1, 1);
+}
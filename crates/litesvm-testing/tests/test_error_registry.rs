@@ -0,0 +1,105 @@
+//! # Error Decoding
+//!
+//! Error assertions describe the custom program error they observed through the registry in
+//! `error_registry`, naming the program that returned it and the variant of its error enum.
+//!
+//! SPL Token is decoded out of the box. `MintCode` mirrors the first codes of its
+//! `TokenError`, registered against a stand-in program id, as a test suite would register
+//! its own program's enum.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    num_derive::FromPrimitive,
+    solana_instruction::error::InstructionError,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    solana_transaction_error::TransactionError,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+#[derive(Debug, FromPrimitive)]
+enum MintCode {
+    NotRentExempt = 0,
+}
+
+/// Initializes the same mint twice; the second `InitializeMint2` (index 2) fails with
+/// `TokenError::AlreadyInUse`.
+fn setup_mint_initialized_twice() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let initialize =
+        initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize.clone(),
+            initialize,
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn decode_error_names_the_failing_program() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    let decoded = decode_error(&result).unwrap();
+    assert_eq!(decoded.program_id, Some(spl_token::ID));
+    assert_eq!(
+        decoded.to_string(),
+        "spl_token::AlreadyInUse (0x6) in instruction 2"
+    );
+}
+
+#[test]
+fn check_instruction_error_reports_the_decoded_error() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    assert_eq!(
+        check_instruction_error(InstructionError::Custom(1), &result),
+        Err(DemandError::WrongError {
+            expected: InstructionError::Custom(1).to_string(),
+            observed: "spl_token::AlreadyInUse (0x6)".to_string(),
+            index: Some(2),
+        })
+    );
+}
+
+#[test]
+fn registered_program_errors() {
+    let program_id = Pubkey::new_unique();
+    assert_eq!(error_name(&program_id, 0), None);
+
+    register_program_errors::<MintCode>(program_id, "mint");
+    assert_eq!(
+        error_name(&program_id, 0),
+        Some("mint::NotRentExempt".to_string())
+    );
+    assert_eq!(error_name(&program_id, 1), None);
+}
+
+#[test]
+#[should_panic(expected = "but got spl_token::AlreadyInUse (0x6) at index 2")]
+fn demand_transaction_error_panics_with_the_decoded_error() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_transaction_error(TransactionError::AlreadyProcessed);
+}
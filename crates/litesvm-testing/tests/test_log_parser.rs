@@ -0,0 +1,122 @@
+//! # Structured Log Parsing
+//!
+//! `parse_logs` rebuilds the invocation tree behind a transaction's flat logs. Creating an
+//! associated token account is a good subject: the ATA program CPIs into both the SPL Token
+//! and system programs, and SPL Token sets return data along the way.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    solana_keypair::Keypair, //
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::instruction::create_associated_token_account,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+#[test]
+fn parse_ata_creation_logs() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let owner = Pubkey::new_unique();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &owner,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+
+    let invocations = parse_logs(&result);
+    let programs: Vec<_> = invocations.iter().map(|i| i.program_id).collect();
+    assert_eq!(
+        programs,
+        vec![
+            system_program::ID,
+            spl_token::ID,
+            spl_associated_token_account::ID
+        ]
+    );
+    assert!(invocations.iter().all(|i| i.depth == 1 && i.succeeded()));
+
+    // The system program is a builtin and logs no compute units
+    assert_eq!(invocations[0].compute_units_consumed, None);
+    assert_eq!(invocations[1].logs, vec!["Instruction: InitializeMint2"]);
+    assert!(invocations[1].compute_units_consumed.is_some());
+
+    let ata = &invocations[2];
+    assert_eq!(
+        ata.logs,
+        vec!["Create", "Initialize the associated token account"]
+    );
+
+    let cpis: Vec<_> = ata.children.iter().map(|c| c.program_id).collect();
+    assert_eq!(
+        cpis,
+        vec![
+            spl_token::ID,
+            system_program::ID,
+            spl_token::ID,
+            spl_token::ID
+        ]
+    );
+    assert!(ata.children.iter().all(|c| c.depth == 2));
+
+    // GetAccountDataSize returns the token account size as a little-endian u64
+    let get_size = &ata.children[0];
+    assert_eq!(get_size.logs, vec!["Instruction: GetAccountDataSize"]);
+    assert_eq!(
+        get_size.return_data,
+        Some(
+            (spl_token::state::Account::LEN as u64)
+                .to_le_bytes()
+                .to_vec()
+        )
+    );
+
+    assert_eq!(ata.iter().count(), 5);
+}
+
+#[test]
+fn parse_failed_transaction_logs() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    // Initializing a mint account that doesn't exist fails inside SPL Token
+    let tx = Transaction::new_signed_with_payer(
+        &[initialize_mint2(
+            &spl_token::ID,
+            &Pubkey::new_unique(),
+            &fee_payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap()],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(result.is_err());
+
+    let invocations = parse_logs(&result);
+    assert_eq!(invocations.len(), 1);
+    assert!(matches!(invocations[0].status, InvocationStatus::Failed(_)));
+}
@@ -0,0 +1,145 @@
+//! # Pattern Log Assertions
+//!
+//! Regular expressions, ordered subsequences, absences and counts over a transaction's log
+//! lines. Creating an associated token account produces a rich, stable log to match
+//! against: the ATA program logs `Create`, then SPL Token logs three instructions via CPI.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::instruction::create_associated_token_account,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+fn setup_mint_and_ata_scenario() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &Pubkey::new_unique(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn demand_log_patterns_direct() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    demand_logs_match(r"consumed \d+ of \d+ compute units", result.clone());
+    demand_logs_in_order(
+        &[
+            "Instruction: InitializeMint2",
+            "Create",
+            "Instruction: InitializeImmutableOwner",
+            "Instruction: InitializeAccount3",
+        ],
+        result.clone(),
+    );
+    demand_logs_not_contain("failed", result.clone());
+    demand_log_count("Program log: Instruction:", 4, result);
+}
+
+#[test]
+fn demand_log_patterns_chained() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_logs_match_and(r"^Program log: Instruction: Initialize\w+3$")
+        .demand_logs_in_order_and(&["invoke [1]", "invoke [2]", "success"])
+        .demand_logs_not_contain_and("Error")
+        .demand_log_count_and("invoke [2]", 4)
+        .accept();
+}
+
+#[test]
+fn check_logs_in_order_reports_where_the_sequence_broke() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    // "InitializeMint2" is logged before "Create", not after
+    let Err(DemandError::LogSequenceBroken {
+        expected, matched, ..
+    }) = check_logs_in_order(
+        &["Create", "InitializeMint2", "InitializeAccount3"],
+        &result,
+    )
+    else {
+        panic!("expected a broken sequence");
+    };
+
+    assert_eq!(expected.len(), 3);
+    assert_eq!(matched.len(), 1);
+    assert!(result.as_ref().unwrap().logs[matched[0]].contains("Create"));
+}
+
+#[test]
+fn check_log_count_and_absence_report_matching_lines() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+    let logs = result.as_ref().unwrap().logs.clone();
+
+    let immutable_owner = logs
+        .iter()
+        .position(|log| log.contains("InitializeImmutableOwner"))
+        .unwrap();
+
+    assert_eq!(
+        check_log_count("InitializeImmutableOwner", 2, &result),
+        Err(DemandError::WrongLogCount {
+            expected: "InitializeImmutableOwner".to_string(),
+            expected_count: 2,
+            matches: vec![(immutable_owner, logs[immutable_owner].clone())],
+        })
+    );
+
+    assert_eq!(
+        check_logs_not_contain("InitializeImmutableOwner", &result),
+        Err(DemandError::UnexpectedLog {
+            unexpected: "InitializeImmutableOwner".to_string(),
+            matches: vec![(immutable_owner, logs[immutable_owner].clone())],
+        })
+    );
+
+    assert!(matches!(
+        check_logs_match(r"Transferred \d+", &result),
+        Err(DemandError::PatternNotFound { .. })
+    ));
+    assert!(matches!(
+        check_logs_match(r"unclosed (group", &result),
+        Err(DemandError::InvalidPattern { .. })
+    ));
+}
+
+#[test]
+#[should_panic(expected = "missing \"InitializeMint2\"")]
+fn demand_logs_in_order_panics_with_progress() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_logs_in_order(&["Create", "InitializeMint2"]);
+}
@@ -0,0 +1,130 @@
+//! # Log Snapshots
+//!
+//! Snapshots lock down a transaction's whole log against a file in `tests/snapshots/`.
+//! The recorded files are normalized: the fresh keypairs each test creates are aliased,
+//! return data is hashed, and compute units can be masked - so they stay stable from run to
+//! run.
+//!
+//! To re-record after an intended change:
+//!
+//! ```text
+//! LITESVM_UPDATE_SNAPSHOTS=1 cargo test --test test_log_snapshots
+//! ```
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::{keypair_from_seed, Keypair},
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::instruction::create_associated_token_account,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+/// The mint and owner are fixed: the ATA program's PDA bump search, and so its compute
+/// units, depend on them.
+fn setup_mint_and_ata_scenario() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = keypair_from_seed(&[1; 32]).unwrap();
+    let owner = Pubkey::new_from_array([2; 32]);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &owner,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+/// Creates `account`, then returns a transaction that tries to create it again.
+fn setup_account_in_use_scenario(account: &Keypair) -> (LiteSVM, Transaction) {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+
+    let create = |svm: &LiteSVM| {
+        Transaction::new_signed_with_payer(
+            &[create_account(
+                &fee_payer.pubkey(),
+                &account.pubkey(),
+                1_000_000,
+                0,
+                &system_program::ID,
+            )],
+            Some(&fee_payer.pubkey()),
+            &[&fee_payer, account],
+            svm.latest_blockhash(),
+        )
+    };
+
+    svm.send_transaction(create(&svm)).unwrap();
+    svm.expire_blockhash();
+
+    let tx = create(&svm);
+    (svm, tx)
+}
+
+#[test]
+fn demand_logs_snapshot_direct() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    demand_logs_snapshot("mint_and_ata", result);
+}
+
+#[test]
+fn demand_logs_snapshot_chained() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_logs_snapshot_and("mint_and_ata")
+        .demand_logs_contain_and("InitializeAccount3")
+        .accept();
+}
+
+#[test]
+fn log_snapshot_with_aliases_and_masked_compute_units() {
+    let account = Keypair::new();
+    let (mut svm, tx) = setup_account_in_use_scenario(&account);
+    let result = svm.send_transaction(tx);
+
+    let snapshot = LogSnapshot::new()
+        .alias(account.pubkey(), "new_account")
+        .mask_compute_units();
+    snapshot.demand("account_already_in_use", result.clone());
+
+    // Without the alias, the account still normalizes to a stable placeholder
+    let unnamed = LogSnapshot::new().normalize(&result);
+    assert!(unnamed.iter().any(|log| log.contains("<pubkey_1>")));
+    assert!(unnamed
+        .iter()
+        .all(|log| !log.contains(&account.pubkey().to_string())));
+}
+
+#[test]
+fn normalize_hashes_return_data() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let logs = LogSnapshot::new().normalize(&svm.send_transaction(tx));
+
+    // GetAccountDataSize returns the token account size as a little-endian u64
+    assert!(logs
+        .iter()
+        .any(|log| log.starts_with("Program return: spl_token <8 bytes ")));
+}
@@ -0,0 +1,148 @@
+//! # Program Error Assertions
+//!
+//! Native and Pinocchio programs usually define their errors as a plain enum, converted to
+//! `ProgramError::Custom(code)` on the way out and decoded with `num_derive::FromPrimitive`
+//! on the way back. `demand_program_error` works with any such enum.
+//!
+//! SPL Token is a native program built this way. `TokenCode` below mirrors the first codes
+//! of its `TokenError`, the way a test suite would mirror its own program's enum. Observed
+//! errors are named by the error registry when it knows the failing program, since the
+//! caller's enum only describes its own program's codes.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    num_derive::FromPrimitive,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+#[derive(Debug, PartialEq, FromPrimitive)]
+enum TokenCode {
+    NotRentExempt = 0,
+    InsufficientFunds = 1,
+    InvalidMint = 2,
+    MintMismatch = 3,
+    OwnerMismatch = 4,
+    FixedSupply = 5,
+    AlreadyInUse = 6,
+}
+
+impl From<TokenCode> for u32 {
+    fn from(code: TokenCode) -> u32 {
+        code as u32
+    }
+}
+
+/// An enum that only knows some of the program's codes.
+#[derive(Debug, PartialEq, FromPrimitive)]
+enum PartialCode {
+    NotRentExempt = 0,
+}
+
+impl From<PartialCode> for u32 {
+    fn from(code: PartialCode) -> u32 {
+        code as u32
+    }
+}
+
+/// Initializes the same mint twice; the second `InitializeMint2` (index 2) fails with
+/// `TokenError::AlreadyInUse`.
+fn setup_mint_initialized_twice() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let initialize =
+        initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize.clone(),
+            initialize,
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn demand_program_error_direct() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    demand_program_error(TokenCode::AlreadyInUse, result.clone());
+    demand_program_error_at_index(2, TokenCode::AlreadyInUse, result);
+}
+
+#[test]
+fn demand_program_error_fluent() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_program_error_at_index(2, TokenCode::AlreadyInUse);
+}
+
+#[test]
+fn demand_program_error_chained() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_program_error_and(TokenCode::AlreadyInUse)
+        .demand_program_error_at_index_and(2, TokenCode::AlreadyInUse)
+        .demand_logs_contain_and("already in use")
+        .accept();
+}
+
+#[test]
+fn check_program_error_reports_decoded_variants() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    assert_eq!(
+        check_program_error_at_index(2, TokenCode::InvalidMint, &result),
+        Err(DemandError::WrongError {
+            expected: "InvalidMint".to_string(),
+            observed: "spl_token::AlreadyInUse (0x6)".to_string(),
+            index: Some(2),
+        })
+    );
+    assert_eq!(
+        check_program_error_at_index(1, TokenCode::AlreadyInUse, &result),
+        Err(DemandError::WrongIndex {
+            expected: "AlreadyInUse".to_string(),
+            expected_index: 1,
+            observed_index: 2,
+        })
+    );
+    // Code 6 means nothing to `PartialCode`, but the registry knows SPL Token's codes
+    assert_eq!(
+        check_program_error(PartialCode::NotRentExempt, &result),
+        Err(DemandError::WrongError {
+            expected: "NotRentExempt".to_string(),
+            observed: "spl_token::AlreadyInUse (0x6)".to_string(),
+            index: None,
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected MintMismatch but got spl_token::AlreadyInUse (0x6)")]
+fn demand_program_error_panics_with_variant_names() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_program_error(TokenCode::MintMismatch);
+}
@@ -0,0 +1,112 @@
+//! # Transaction Reports
+//!
+//! `describe` renders what a transaction did, and every failing `demand_*` assertion prints
+//! it after the failure message. The scenario creates and initializes a mint, then an
+//! associated token account, whose creation makes four CPIs.
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::{
+        get_associated_token_address, instruction::create_associated_token_account,
+    },
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+struct AtaScenario {
+    svm: LiteSVM,
+    tx: Transaction,
+    address_book: AddressBook,
+}
+
+fn setup_mint_and_ata_scenario() -> AtaScenario {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let owner = Pubkey::new_unique();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &owner,
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    let mut address_book = AddressBook::empty()
+        .with(mint.pubkey(), "mint")
+        .with(owner, "owner");
+    address_book.insert_under(
+        &owner,
+        "ata",
+        get_associated_token_address(&owner, &mint.pubkey()),
+    );
+
+    AtaScenario {
+        svm,
+        tx,
+        address_book,
+    }
+}
+
+#[test]
+fn describe_renders_the_invocation_tree() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+    let report = describe(&result);
+
+    assert!(report.contains("  status:        succeeded\n"));
+    assert!(report.contains("  fee:           unknown without the transaction\n"));
+    assert!(report.contains("    [0] system_program: success\n"));
+    assert!(report.contains("    [2] spl_associated_token_account: success, "));
+    assert!(report.contains("        -> spl_token: success, "));
+    assert!(report.contains("              log: Instruction: GetAccountDataSize\n"));
+    assert!(report.contains("              return: a500000000000000\n"));
+}
+
+#[test]
+fn transaction_report_resolves_accounts_and_fee() {
+    let AtaScenario {
+        mut svm,
+        tx,
+        address_book,
+    } = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx.clone());
+
+    let report = TransactionReport::new(&result)
+        .transaction(&tx)
+        .address_book(&address_book)
+        .to_string();
+
+    // Fee payer and mint sign
+    assert!(report.contains("  fee:           10000 lamports\n"));
+    assert!(report.contains("    [2] spl_token at depth 2, data 12"));
+    assert!(report.contains("accounts [owner.ata, mint]"));
+}
+
+#[test]
+#[should_panic(expected = "    [2] spl_associated_token_account: success")]
+fn demand_failures_include_the_report() {
+    let AtaScenario { mut svm, tx, .. } = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_logs_contain("Instruction: Transfer");
+}
@@ -0,0 +1,136 @@
+//! # Return Data Assertions
+//!
+//! SPL Token has a few instructions that answer questions through `set_return_data`, which
+//! makes it a convenient subject:
+//!
+//! - `GetAccountDataSize` returns the token account size as a little-endian `u64` (also its
+//!   Borsh encoding)
+//! - `AmountToUiAmount` returns the UI amount as a UTF-8 string, without Borsh framing
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::{create_account, transfer},
+    solana_transaction::Transaction,
+    spl_token::{
+        instruction::{amount_to_ui_amount, get_account_data_size, initialize_mint2},
+        solana_program::program_pack::Pack,
+    },
+};
+
+/// Creates a 6-decimal mint, then runs `query` against it as the last instruction.
+fn setup_mint_query_scenario(query: fn(&Pubkey) -> Instruction) -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            query(&mint.pubkey()),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+fn account_data_size(mint: &Pubkey) -> Instruction {
+    get_account_data_size(&spl_token::ID, mint).unwrap()
+}
+
+fn ui_amount(mint: &Pubkey) -> Instruction {
+    amount_to_ui_amount(&spl_token::ID, mint, 1_500_000).unwrap()
+}
+
+const ACCOUNT_LEN: u64 = spl_token::state::Account::LEN as u64;
+
+#[test]
+fn demand_return_data_direct() {
+    let (mut svm, tx) = setup_mint_query_scenario(account_data_size);
+    let result = svm.send_transaction(tx);
+
+    demand_return_data(&spl_token::ID, &ACCOUNT_LEN.to_le_bytes(), result.clone());
+    demand_return_data_decodes::<u64>(|size| *size == ACCOUNT_LEN, result.clone());
+
+    assert_eq!(decode_return_data::<u64>(&result), Some(ACCOUNT_LEN));
+}
+
+#[test]
+fn demand_return_data_chained() {
+    let (mut svm, tx) = setup_mint_query_scenario(ui_amount);
+
+    svm.send_transaction(tx)
+        .demand_return_data_and(&spl_token::ID, b"1.5")
+        .demand_logs_contain_and("Instruction: AmountToUiAmount")
+        .accept();
+}
+
+#[test]
+fn check_return_data_reports_what_was_returned() {
+    let (mut svm, tx) = setup_mint_query_scenario(ui_amount);
+    let result = svm.send_transaction(tx);
+
+    assert_eq!(return_data(&result), Some((spl_token::ID, &b"1.5"[..])));
+
+    assert_eq!(
+        check_return_data(&spl_token::ID, b"1.50", &result),
+        Err(DemandError::ReturnDataMismatch {
+            expected: format!("return data 312e3530 from {}", spl_token::ID),
+            program_id: spl_token::ID,
+            data: b"1.5".to_vec(),
+            error: None,
+        })
+    );
+
+    // Three bytes aren't a Borsh u64
+    assert!(matches!(
+        check_return_data_decodes::<u64>(|_| true, &result),
+        Err(DemandError::ReturnDataMismatch { error: Some(_), .. })
+    ));
+}
+
+#[test]
+fn check_return_data_when_none_was_set() {
+    let (mut svm, fee_payer) = setup_svm_and_fee_payer();
+    let tx = Transaction::new_signed_with_payer(
+        &[transfer(
+            &fee_payer.pubkey(),
+            &Pubkey::new_unique(),
+            1_000_000,
+        )],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer],
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+
+    assert_eq!(return_data(&result), None);
+    assert_eq!(
+        check_return_data_decodes::<u64>(|_| true, &result),
+        Err(DemandError::ReturnDataMissing {
+            expected: "return data decoding as u64".to_string(),
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "matching the predicate but spl_token returned a500000000000000")]
+fn demand_return_data_decodes_panics_with_the_data() {
+    let (mut svm, tx) = setup_mint_query_scenario(account_data_size);
+
+    svm.send_transaction(tx)
+        .demand_return_data_decodes::<u64>(|size| *size == 0);
+}
@@ -0,0 +1,120 @@
+//! # Scoped Log Assertions
+//!
+//! Raw log indices shift whenever a CPI or log line is added. These tests scope assertions
+//! to a program, a CPI depth, or a top-level instruction instead, using a transaction where
+//! SPL Token runs both directly (depth 1) and via CPI from the ATA program (depth 2):
+//!
+//! - `[0]` system program: create the mint account
+//! - `[1]` SPL Token: `InitializeMint2`
+//! - `[2]` ATA program: `Create`, CPIing into SPL Token and the system program
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::instruction::create_account,
+    solana_transaction::Transaction,
+    spl_associated_token_account::instruction::create_associated_token_account,
+    spl_token::{instruction::initialize_mint2, solana_program::program_pack::Pack},
+};
+
+fn setup_mint_and_ata_scenario() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_account(
+                &fee_payer.pubkey(),
+                &mint.pubkey(),
+                svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &Pubkey::new_unique(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn demand_scoped_logs_direct() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    demand_program_logged(&spl_token::ID, "InitializeAccount3", result.clone());
+    demand_logged_at_depth(&spl_token::ID, 1, "InitializeMint2", result.clone());
+    demand_logged_at_depth(&spl_token::ID, 2, "InitializeAccount3", result.clone());
+    demand_instruction_logged(2, "InitializeAccount3", result);
+}
+
+#[test]
+fn demand_scoped_logs_chained() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_program_logged_and(&spl_associated_token_account::ID, "Create")
+        .demand_logged_at_depth_and(&spl_token::ID, 2, "GetAccountDataSize")
+        .demand_instruction_logged_and(1, "InitializeMint2")
+        .accept();
+}
+
+#[test]
+fn check_scoped_logs_report_only_the_scope() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+    let result = svm.send_transaction(tx);
+
+    // SPL Token only logged InitializeMint2 when invoked directly
+    assert_eq!(
+        check_logged_at_depth(&spl_token::ID, 1, "InitializeAccount3", &result),
+        Err(DemandError::ScopedLogNotFound {
+            expected: "InitializeAccount3".to_string(),
+            scope: format!("program {} at depth 1", spl_token::ID),
+            logs: Some(vec!["Instruction: InitializeMint2".to_string()]),
+        })
+    );
+
+    // The ATA instruction's scope includes its CPIs, but not earlier instructions
+    assert!(matches!(
+        check_instruction_logged(2, "InitializeMint2", &result),
+        Err(DemandError::ScopedLogNotFound { logs: Some(logs), .. })
+            if logs.contains(&"Instruction: InitializeAccount3".to_string())
+    ));
+
+    let missing = Pubkey::new_unique();
+    assert_eq!(
+        check_program_logged(&missing, "anything", &result),
+        Err(DemandError::ScopedLogNotFound {
+            expected: "anything".to_string(),
+            scope: format!("program {}", missing),
+            logs: None,
+        })
+    );
+    assert!(matches!(
+        check_instruction_logged(3, "anything", &result),
+        Err(DemandError::ScopedLogNotFound { logs: None, .. })
+    ));
+}
+
+#[test]
+#[should_panic(
+    expected = "among 1 log messages of instruction 1: [0]: Instruction: InitializeMint2"
+)]
+fn demand_instruction_logged_panics_with_scoped_logs() {
+    let (mut svm, tx) = setup_mint_and_ata_scenario();
+
+    svm.send_transaction(tx)
+        .demand_instruction_logged(1, "InitializeAccount3");
+}
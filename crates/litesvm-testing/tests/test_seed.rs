@@ -0,0 +1,25 @@
+//! # Random Seeds
+//!
+//! With `LITESVM_SEED=random`, each test gets its own seed, and every context and keypair in
+//! the test derives from it. This binary sets the variable itself, so it holds one test.
+
+use litesvm_testing::{prelude::*, seed::SEED_ENV};
+
+#[test]
+fn random_seeds_are_fixed_for_the_test() {
+    std::env::set_var(SEED_ENV, "random");
+
+    let seed = test_seed();
+    assert_eq!(test_seed(), seed);
+    assert_eq!(
+        TestContext::new().payer().pubkey(),
+        TestContext::new().payer().pubkey()
+    );
+
+    let guard = SeedGuard::new(seed);
+    assert_eq!(guard.seed(), seed);
+
+    // Another test, on another thread, draws a seed of its own
+    let other = std::thread::spawn(test_seed).join().unwrap();
+    assert_ne!(other, seed);
+}
@@ -10,6 +10,7 @@
 //! - **Better**: `InstructionError` level (removes transaction nesting, cleaner)
 //! - **Best**: `SystemError` level (type-safe, no casting, most ergonomic)
 //! - **Best+**: `SystemError` with explicit index control (surgical precision)
+//! - **Chained**: Several assertions on one result with `DemandChaining`
 //!
 //! ## Testing Styles
 //!
@@ -191,3 +192,19 @@ fn demand_system_error_at_index_fluently() {
     svm.send_transaction(tx)
         .demand_system_error_at_index(1, SystemError::ResultWithNegativeLamports);
 }
+
+/// **Chained Approach**: Several assertions against the same result.
+///
+/// Each `_and` method returns the result, so one transaction can be checked for both
+/// its error and its logs without cloning or re-running it. `accept()` ends the chain.
+#[test]
+fn demand_system_error_and_logs_chained() {
+    let (mut svm, tx) = setup_insufficient_funds_scenario();
+    svm.send_transaction(tx)
+        .demand_system_error_at_index_and(1, SystemError::ResultWithNegativeLamports)
+        .demand_instruction_error_and(InstructionError::Custom(
+            SystemError::ResultWithNegativeLamports as u32,
+        ))
+        .demand_logs_contain_and("insufficient lamports 1000, need 500000")
+        .accept();
+}
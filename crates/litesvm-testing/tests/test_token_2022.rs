@@ -0,0 +1,155 @@
+//! # Token-2022 Extensions
+//!
+//! `MintBuilder` writes Token-2022 mints with extensions, and `create_ata` gives their
+//! accounts the extensions the mint requires. These tests run real Token-2022 instructions
+//! against that state, then check it with the extension assertions.
+
+#![cfg(feature = "token")]
+
+use litesvm_testing::{prelude::*, token_testing::*};
+
+use spl_token_2022::{extension::ExtensionType, instruction};
+
+#[test]
+fn transfer_fees_are_withheld_in_the_destination() {
+    let mut ctx = TestContext::new();
+    let authority = ctx.actor("authority");
+    let alice = ctx.actor("alice");
+    let bob = ctx.actor("bob");
+
+    let mint = MintBuilder::new(&authority.pubkey(), 6)
+        .transfer_fee(100, 1_000_000)
+        .create(ctx.svm_mut());
+    let alice_ata = funded_token_account(ctx.svm_mut(), &alice.pubkey(), &mint, 1_000_000);
+    let bob_ata = create_ata(ctx.svm_mut(), &bob.pubkey(), &mint);
+
+    demand_transfer_fee(ctx.svm(), &mint, 100, 1_000_000);
+    demand_has_extension(ctx.svm(), &bob_ata, ExtensionType::TransferFeeAmount);
+    demand_has_extension(ctx.svm(), &bob_ata, ExtensionType::ImmutableOwner);
+
+    let transfer = instruction::transfer_checked(
+        &spl_token_2022::ID,
+        &alice_ata,
+        &mint,
+        &bob_ata,
+        &alice.pubkey(),
+        &[],
+        10_000,
+        6,
+    )
+    .unwrap();
+    ctx.send(&[transfer], &[&alice])
+        .demand_logs_contain("Instruction: TransferChecked");
+
+    demand_token_balance(ctx.svm(), &alice_ata, 990_000);
+    demand_token_balance(ctx.svm(), &bob_ata, 9_900);
+    demand_withheld_fees(ctx.svm(), &bob_ata, 100);
+    demand_withheld_fees(ctx.svm(), &mint, 0);
+}
+
+#[test]
+fn mint_extension_state() {
+    let mut ctx = TestContext::new();
+    let authority = ctx.actor("authority");
+    let hook_program = Pubkey::new_unique();
+    let mint = ctx.keypair("mint").pubkey();
+
+    MintBuilder::new(&authority.pubkey(), 2)
+        .address(&mint)
+        .freeze_authority(&authority.pubkey())
+        .transfer_hook(&hook_program)
+        .interest_bearing(500)
+        .confidential_transfers(true)
+        .metadata_pointer(&mint)
+        .create(ctx.svm_mut());
+    let ata = create_ata(ctx.svm_mut(), &Pubkey::new_unique(), &mint);
+
+    // Token-2022 accepts the mint and its account
+    let mint_to = instruction::mint_to(
+        &spl_token_2022::ID,
+        &mint,
+        &ata,
+        &authority.pubkey(),
+        &[],
+        12_345,
+    )
+    .unwrap();
+    ctx.send(&[mint_to], &[&authority])
+        .demand_logs_contain("Instruction: MintTo");
+
+    demand_mint_supply(ctx.svm(), &mint, 12_345);
+    demand_transfer_hook(ctx.svm(), &mint, &hook_program);
+    demand_interest_rate(ctx.svm(), &mint, 500);
+    demand_metadata_pointer(ctx.svm(), &mint, &mint);
+    demand_has_extension(ctx.svm(), &mint, ExtensionType::ConfidentialTransferMint);
+    demand_has_extension(ctx.svm(), &ata, ExtensionType::TransferHookAccount);
+    assert_eq!(
+        ctx.svm().get_account(&ata).unwrap().owner,
+        spl_token_2022::ID
+    );
+}
+
+#[test]
+fn token_program_can_be_chosen() {
+    let mut ctx = TestContext::new();
+    let authority = Pubkey::new_unique();
+
+    let mint = MintBuilder::new(&authority, 9)
+        .token_program(&spl_token_2022::ID)
+        .create(ctx.svm_mut());
+    let ata = funded_token_account(ctx.svm_mut(), &authority, &mint, 7);
+
+    assert_eq!(
+        ctx.svm().get_account(&mint).unwrap().owner,
+        spl_token_2022::ID
+    );
+    assert_eq!(
+        ata,
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            &authority,
+            &mint,
+            &spl_token_2022::ID
+        )
+    );
+    demand_token_balance(ctx.svm(), &ata, 7);
+}
+
+#[test]
+fn hook_and_metadata_are_compared_by_pubkey() {
+    let mut ctx = TestContext::new();
+    let (hook, other_hook) = (Pubkey::new_unique(), Pubkey::new_unique());
+    AddressBook::empty()
+        .with(hook, "hook")
+        .with(other_hook, "hook")
+        .share();
+
+    let mint = MintBuilder::new(&Pubkey::new_unique(), 6)
+        .transfer_hook(&hook)
+        .metadata_pointer(&hook)
+        .create(ctx.svm_mut());
+
+    assert!(check_transfer_hook(ctx.svm(), &mint, &other_hook).is_err());
+    assert!(check_metadata_pointer(ctx.svm(), &mint, &other_hook).is_err());
+    check_transfer_hook(ctx.svm(), &mint, &hook).unwrap();
+    check_metadata_pointer(ctx.svm(), &mint, &hook).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "to be TransferHook but got none")]
+fn missing_extension_is_reported() {
+    let mut ctx = TestContext::new();
+    let mint = create_mint(ctx.svm_mut(), &Pubkey::new_unique(), 6);
+
+    demand_transfer_hook(ctx.svm(), &mint, &Pubkey::new_unique());
+}
+
+#[test]
+#[should_panic(expected = "need Token-2022")]
+fn spl_token_mints_have_no_extensions() {
+    let mut ctx = TestContext::new();
+
+    MintBuilder::new(&Pubkey::new_unique(), 6)
+        .token_program(&spl_token::ID)
+        .transfer_fee(100, 1_000)
+        .create(ctx.svm_mut());
+}
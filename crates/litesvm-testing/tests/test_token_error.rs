@@ -0,0 +1,258 @@
+//! # Token Error Assertions
+//!
+//! `TokenError`, `AssociatedTokenAccountError` and `SystemError` all travel as
+//! `InstructionError::Custom(code)`, and their codes overlap: `0x6` is SPL Token's
+//! `AlreadyInUse` but the system program's `NonceNoRecentBlockhashes`. The token and ATA
+//! assertions (and the system ones) check which program raised the code before decoding it.
+//!
+//! Scenarios:
+//! - **Mint initialized twice**: SPL Token fails instruction `[2]` directly
+//! - **ATA for an uninitialized mint**: SPL Token fails inside the ATA program's CPI
+//! - **ATA handed to another owner**: the ATA program fails itself, at instruction `[4]`
+
+use litesvm_testing::{prelude::*, setup_svm_and_fee_payer};
+
+use {
+    litesvm::LiteSVM, //
+    solana_instruction::Instruction,
+    solana_keypair::Keypair,
+    solana_signer::Signer,
+    solana_system_interface::{error::SystemError, instruction::create_account},
+    solana_transaction::Transaction,
+    spl_associated_token_account::{
+        error::AssociatedTokenAccountError,
+        get_associated_token_address,
+        instruction::{
+            create_associated_token_account, create_associated_token_account_idempotent,
+        },
+    },
+    spl_token::{
+        error::TokenError,
+        instruction::{initialize_mint2, set_authority, AuthorityType},
+        solana_program::program_pack::Pack,
+        state::Mint,
+    },
+};
+
+fn create_mint_account(svm: &LiteSVM, fee_payer: &Keypair, mint: &Keypair) -> Instruction {
+    create_account(
+        &fee_payer.pubkey(),
+        &mint.pubkey(),
+        svm.minimum_balance_for_rent_exemption(Mint::LEN),
+        Mint::LEN as u64,
+        &spl_token::ID,
+    )
+}
+
+/// Initializes the same mint twice; the second `InitializeMint2` (index 2) fails with
+/// `TokenError::AlreadyInUse`.
+fn setup_mint_initialized_twice() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let initialize =
+        initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_mint_account(&svm, &fee_payer, &mint),
+            initialize.clone(),
+            initialize,
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+/// Creates an ATA (index 1) for a mint account that was never initialized; SPL Token
+/// rejects the mint inside the ATA program's first CPI.
+fn setup_ata_for_uninitialized_mint() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_mint_account(&svm, &fee_payer, &mint),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &Pubkey::new_unique(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+/// Creates the owner's ATA, hands it to someone else with `SetAuthority` (SPL Token doesn't
+/// enforce immutable owners), then asks for the owner's ATA again (index 4). The ATA program
+/// finds an account it didn't derive for that owner and fails with `InvalidOwner`.
+fn setup_ata_with_new_owner() -> (LiteSVM, Transaction) {
+    let (svm, fee_payer) = setup_svm_and_fee_payer();
+    let mint = Keypair::new();
+    let owner = Keypair::new();
+    let ata = get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_mint_account(&svm, &fee_payer, &mint),
+            initialize_mint2(&spl_token::ID, &mint.pubkey(), &fee_payer.pubkey(), None, 6).unwrap(),
+            create_associated_token_account(
+                &fee_payer.pubkey(),
+                &owner.pubkey(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+            set_authority(
+                &spl_token::ID,
+                &ata,
+                Some(&Pubkey::new_unique()),
+                AuthorityType::AccountOwner,
+                &owner.pubkey(),
+                &[],
+            )
+            .unwrap(),
+            create_associated_token_account_idempotent(
+                &fee_payer.pubkey(),
+                &owner.pubkey(),
+                &mint.pubkey(),
+                &spl_token::ID,
+            ),
+        ],
+        Some(&fee_payer.pubkey()),
+        &[&fee_payer, &mint, &owner],
+        svm.latest_blockhash(),
+    );
+
+    (svm, tx)
+}
+
+#[test]
+fn demand_token_error_direct() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    demand_token_error(TokenError::AlreadyInUse, result.clone());
+    demand_token_error_at_index(2, TokenError::AlreadyInUse, result);
+}
+
+#[test]
+fn demand_token_error_chained() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_token_error_and(TokenError::AlreadyInUse)
+        .demand_token_error_at_index_and(2, TokenError::AlreadyInUse)
+        .demand_logs_contain_and("already in use")
+        .accept();
+}
+
+#[test]
+fn demand_token_error_raised_via_cpi() {
+    let (mut svm, tx) = setup_ata_for_uninitialized_mint();
+    let result = svm.send_transaction(tx);
+
+    // The ATA program failed instruction 1, but SPL Token raised the code
+    assert_eq!(failed_program(&result), Some(spl_token::ID));
+    result
+        .clone()
+        .demand_token_error_at_index(1, TokenError::InvalidMint);
+
+    assert_eq!(
+        check_associated_token_error(AssociatedTokenAccountError::InvalidOwner, &result),
+        Err(DemandError::WrongProgram {
+            expected: AssociatedTokenAccountError::InvalidOwner.to_string(),
+            expected_program: spl_associated_token_account::ID,
+            observed_program: spl_token::ID,
+            code: TokenError::InvalidMint as u32,
+            index: 1,
+        })
+    );
+}
+
+#[test]
+fn demand_associated_token_error_direct() {
+    let (mut svm, tx) = setup_ata_with_new_owner();
+    let result = svm.send_transaction(tx);
+
+    demand_associated_token_error(AssociatedTokenAccountError::InvalidOwner, result.clone());
+    result.demand_associated_token_error_at_index(4, AssociatedTokenAccountError::InvalidOwner);
+}
+
+#[test]
+fn check_errors_from_the_wrong_program() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+    let result = svm.send_transaction(tx);
+
+    // Code 6 decodes as SystemError::NonceNoRecentBlockhashes, but the system program
+    // didn't raise it
+    assert_eq!(
+        check_system_error(SystemError::NonceNoRecentBlockhashes, &result),
+        Err(DemandError::WrongProgram {
+            expected: SystemError::NonceNoRecentBlockhashes.to_string(),
+            expected_program: system_program::ID,
+            observed_program: spl_token::ID,
+            code: 6,
+            index: 2,
+        })
+    );
+
+    // Index mismatches are reported before the program
+    assert!(matches!(
+        check_associated_token_error_at_index(
+            1,
+            AssociatedTokenAccountError::InvalidOwner,
+            &result
+        ),
+        Err(DemandError::WrongIndex {
+            expected_index: 1,
+            observed_index: 2,
+            ..
+        })
+    ));
+
+    assert_eq!(
+        check_token_error_at_index(2, TokenError::OwnerMismatch, &result),
+        Err(DemandError::WrongError {
+            expected: TokenError::OwnerMismatch.to_string(),
+            observed: "spl_token::AlreadyInUse (0x6)".to_string(),
+            index: Some(2),
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "but got spl_token::AlreadyInUse (0x6) at index 2 from spl_token")]
+fn demand_system_error_panics_with_the_failing_program() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    svm.send_transaction(tx)
+        .demand_system_error(SystemError::NonceNoRecentBlockhashes);
+}
+
+#[test]
+fn check_errors_from_an_unknown_program() {
+    let (mut svm, tx) = setup_mint_initialized_twice();
+
+    // Without logs, nothing says SPL Token raised code 6
+    let result = svm.send_transaction(tx).map_err(|mut e| {
+        e.meta.logs.clear();
+        e
+    });
+
+    assert_eq!(
+        check_token_error(TokenError::AlreadyInUse, &result),
+        Err(DemandError::UnknownProgram {
+            expected: TokenError::AlreadyInUse.to_string(),
+            expected_program: spl_token::ID,
+            code: 6,
+            index: 2,
+        })
+    );
+}
@@ -0,0 +1,164 @@
+//! # Token State Assertions
+//!
+//! The token state assertions decode SPL Token and Token-2022 accounts alike. These tests
+//! cover both programs: factory-made SPL Token accounts, and Token-2022 accounts created
+//! by real instructions, then approved and frozen. Failures print UI amounts.
+
+#![cfg(feature = "token")]
+
+use litesvm_testing::{prelude::*, token_testing::*};
+
+use solana_system_interface::instruction::create_account;
+use spl_token::solana_program::program_pack::Pack;
+use spl_token_2022::{
+    instruction::{approve, freeze_account, initialize_account3, initialize_mint2, mint_to},
+    state::{Account as TokenAccount, Mint},
+};
+
+#[test]
+fn spl_token_state() {
+    let mut ctx = TestContext::new();
+    let authority = Pubkey::new_unique();
+    let alice = Pubkey::new_unique();
+
+    let usdc = create_mint(ctx.svm_mut(), &authority, 6);
+    let alice_usdc = funded_token_account(ctx.svm_mut(), &alice, &usdc, 1_500_000);
+
+    demand_token_balance(ctx.svm(), &alice_usdc, 1_500_000);
+    demand_token_owner(ctx.svm(), &alice_usdc, &alice);
+    demand_mint_supply(ctx.svm(), &usdc, 1_500_000);
+    assert!(check_frozen(ctx.svm(), &alice_usdc).is_err());
+    assert!(check_delegate(ctx.svm(), &alice_usdc, &authority, 0).is_err());
+}
+
+#[test]
+fn token_2022_state() {
+    let mut ctx = TestContext::new();
+    let authority = ctx.actor("authority");
+    let alice = ctx.actor("alice");
+    let router = Pubkey::new_unique();
+    let mint = ctx.keypair("mint");
+    let account = ctx.keypair("alice.account");
+    let rent = |len| ctx.svm().minimum_balance_for_rent_exemption(len);
+    let (mint_rent, account_rent) = (rent(Mint::LEN), rent(TokenAccount::LEN));
+    let payer = ctx.payer().pubkey();
+    let token_2022 = spl_token_2022::ID;
+
+    let instructions = [
+        create_account(
+            &payer,
+            &mint.pubkey(),
+            mint_rent,
+            Mint::LEN as u64,
+            &token_2022,
+        ),
+        initialize_mint2(
+            &token_2022,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            Some(&authority.pubkey()),
+            2,
+        )
+        .unwrap(),
+        create_account(
+            &payer,
+            &account.pubkey(),
+            account_rent,
+            TokenAccount::LEN as u64,
+            &token_2022,
+        ),
+        initialize_account3(
+            &token_2022,
+            &account.pubkey(),
+            &mint.pubkey(),
+            &alice.pubkey(),
+        )
+        .unwrap(),
+        mint_to(
+            &token_2022,
+            &mint.pubkey(),
+            &account.pubkey(),
+            &authority.pubkey(),
+            &[],
+            12_345,
+        )
+        .unwrap(),
+        approve(
+            &token_2022,
+            &account.pubkey(),
+            &router,
+            &alice.pubkey(),
+            &[],
+            500,
+        )
+        .unwrap(),
+        freeze_account(
+            &token_2022,
+            &account.pubkey(),
+            &mint.pubkey(),
+            &authority.pubkey(),
+            &[],
+        )
+        .unwrap(),
+    ];
+    ctx.send(&instructions, &[&mint, &account, &authority, &alice])
+        .demand_logs_contain("Instruction: FreezeAccount");
+
+    demand_token_balance(ctx.svm(), &account.pubkey(), 12_345);
+    demand_token_owner(ctx.svm(), &account.pubkey(), &alice.pubkey());
+    demand_delegate(ctx.svm(), &account.pubkey(), &router, 500);
+    demand_frozen(ctx.svm(), &account.pubkey());
+    demand_mint_supply(ctx.svm(), &mint.pubkey(), 12_345);
+}
+
+#[test]
+#[should_panic(
+    expected = "token balance of account alice.usdc_ata to be 0.75 (750000) but got 0.5 (500000)"
+)]
+fn balance_mismatch_reports_ui_amounts() {
+    let mut ctx = TestContext::new().share_labels();
+    let alice = ctx.actor("alice");
+    let usdc = create_mint(ctx.svm_mut(), &Pubkey::new_unique(), 6);
+    let alice_usdc = funded_token_account(ctx.svm_mut(), &alice.pubkey(), &usdc, 500_000);
+    ctx.label(alice_usdc, "alice.usdc_ata");
+
+    demand_token_balance(ctx.svm(), &alice_usdc, 750_000);
+}
+
+#[test]
+fn owners_and_delegates_are_compared_by_pubkey() {
+    let mut ctx = TestContext::new();
+    let (alice, other_alice) = (Pubkey::new_unique(), Pubkey::new_unique());
+    AddressBook::empty()
+        .with(alice, "alice")
+        .with(other_alice, "alice")
+        .share();
+
+    let usdc = create_mint(ctx.svm_mut(), &Pubkey::new_unique(), 6);
+    let ata = funded_token_account(ctx.svm_mut(), &alice, &usdc, 1);
+
+    // Same label, different key
+    let error = check_token_owner(ctx.svm(), &ata, &other_alice).unwrap_err();
+    assert!(matches!(
+        error,
+        DemandError::AccountMismatch {
+            field: "token owner",
+            ..
+        }
+    ));
+    check_token_owner(ctx.svm(), &ata, &alice).unwrap();
+    assert!(check_delegate(ctx.svm(), &ata, &other_alice, 0).is_err());
+}
+
+#[test]
+fn non_token_accounts_are_rejected() {
+    let mut ctx = TestContext::new();
+    let alice = ctx.actor("alice");
+
+    let error = check_token_balance(ctx.svm(), &alice.pubkey(), 0).unwrap_err();
+    assert!(matches!(error, DemandError::NotTokenState { .. }));
+    assert!(error.to_string().contains("owned by system_program"));
+
+    let error = check_mint_supply(ctx.svm(), &Pubkey::new_unique(), 0).unwrap_err();
+    assert!(matches!(error, DemandError::AccountNotFound { .. }));
+}
@@ -0,0 +1,91 @@
+//! # Token Fixtures
+//!
+//! `token_testing` writes mints and token accounts straight into the runtime. These tests
+//! check that the token program accepts them as its own: a real `TransferChecked` moves
+//! tokens between factory-made accounts, and the mint's supply matches what was minted.
+
+#![cfg(feature = "token")]
+
+use litesvm_testing::{prelude::*, token_testing::*};
+
+use spl_token::{
+    instruction::transfer_checked,
+    solana_program::program_pack::Pack,
+    state::{Account as TokenAccount, Mint},
+};
+
+fn unpack_mint(ctx: &TestContext, mint: &Pubkey) -> Mint {
+    Mint::unpack(&ctx.svm().get_account(mint).unwrap().data).unwrap()
+}
+
+#[test]
+fn factory_accounts_work_with_the_token_program() {
+    let mut ctx = TestContext::new();
+    let authority = ctx.actor("authority");
+    let alice = ctx.actor("alice");
+    let bob = ctx.actor("bob");
+
+    let usdc = create_mint(ctx.svm_mut(), &authority.pubkey(), 6);
+    let alice_usdc = funded_token_account(ctx.svm_mut(), &alice.pubkey(), &usdc, 1_000_000);
+    let bob_usdc = create_ata(ctx.svm_mut(), &bob.pubkey(), &usdc);
+
+    let transfer = transfer_checked(
+        &spl_token::ID,
+        &alice_usdc,
+        &usdc,
+        &bob_usdc,
+        &alice.pubkey(),
+        &[],
+        250_000,
+        6,
+    )
+    .unwrap();
+    ctx.send(&[transfer], &[&alice])
+        .demand_logs_contain("Instruction: TransferChecked");
+
+    assert_eq!(token_balance(ctx.svm(), &alice_usdc), 750_000);
+    assert_eq!(token_balance(ctx.svm(), &bob_usdc), 250_000);
+}
+
+#[test]
+fn accounts_are_rent_exempt_and_consistent() {
+    let mut ctx = TestContext::new();
+    let authority = ctx.keypair("authority");
+    let mint = ctx.keypair("mint").pubkey();
+    let owner = Pubkey::new_unique();
+
+    create_mint_at(ctx.svm_mut(), &mint, &authority.pubkey(), 9);
+    let ata = funded_token_account(ctx.svm_mut(), &owner, &mint, 500);
+    mint_to(ctx.svm_mut(), &mint, &ata, 250);
+
+    let state = unpack_mint(&ctx, &mint);
+    assert_eq!(state.decimals, 9);
+    assert_eq!(state.supply, 750);
+    assert_eq!(state.mint_authority, Some(authority.pubkey()).into());
+
+    let account = ctx.svm().get_account(&ata).unwrap();
+    let token_account = TokenAccount::unpack(&account.data).unwrap();
+    assert_eq!(token_account.owner, owner);
+    assert_eq!(token_account.amount, 750);
+    assert_eq!(
+        account.lamports,
+        ctx.svm()
+            .minimum_balance_for_rent_exemption(TokenAccount::LEN)
+    );
+    assert_eq!(
+        ata,
+        spl_associated_token_account::get_associated_token_address(&owner, &mint)
+    );
+}
+
+#[test]
+#[should_panic(expected = "holds mint")]
+fn mint_to_rejects_another_mints_account() {
+    let mut ctx = TestContext::new();
+    let authority = Pubkey::new_unique();
+    let usdc = create_mint(ctx.svm_mut(), &authority, 6);
+    let bonk = create_mint(ctx.svm_mut(), &authority, 5);
+    let usdc_ata = create_ata(ctx.svm_mut(), &Pubkey::new_unique(), &usdc);
+
+    mint_to(ctx.svm_mut(), &bonk, &usdc_ata, 1);
+}
@@ -8,8 +8,15 @@ mod simple_anchor_program {
 
     pub fn log_hello(ctx: Context<LogHello>) -> Result<()> {
         msg!("Hello from anchor! {}", ctx.program_id);
+        emit!(HelloEvent {
+            program_id: *ctx.program_id,
+        });
         Ok(())
     }
+
+    pub fn fail_instruction(_ctx: Context<FailInstruction>) -> Result<()> {
+        err!(SimpleAnchorError::AlwaysFails)
+    }
 }
 
 #[derive(Accounts)]
@@ -17,3 +24,14 @@ pub struct LogHello {}
 
 #[derive(Accounts)]
 pub struct FailInstruction {}
+
+#[event]
+pub struct HelloEvent {
+    pub program_id: Pubkey,
+}
+
+#[error_code]
+pub enum SimpleAnchorError {
+    #[msg("This instruction always fails")]
+    AlwaysFails,
+}
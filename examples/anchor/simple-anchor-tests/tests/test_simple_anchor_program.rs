@@ -1,8 +1,8 @@
 //! # Anchor Program Testing Examples
 //!
-//! This module demonstrates two ways to assert on transaction logs:
+//! This module demonstrates two ways to assert on transaction logs, Anchor errors and events:
 //!
-//! 1. **Direct function call**: `demand_logs_contain(result, "message")`
+//! 1. **Direct function call**: `demand_logs_contain("message", result)`
 //! 2. **Fluent trait method**: `result.demand_logs_contain("message")`
 //!
 //! Both approaches provide the same functionality with detailed error messages
@@ -13,6 +13,7 @@ use litesvm_testing::prelude::*;
 use {
     anchor_lang::{InstructionData, ToAccountMetas},
     litesvm::LiteSVM,
+    simple_anchor_program::{HelloEvent, SimpleAnchorError},
     simple_anchor_tests::load_simple_anchor_program,
     solana_instruction::Instruction,
     solana_keypair::Keypair,
@@ -42,6 +43,62 @@ fn test_use_demand_logs_contain_fluently() {
         .demand_logs_contain("Hello from anchor!");
 }
 
+/// Test decoding an `emit!` event using the direct function call approach
+#[test]
+fn test_use_demand_event_emitted_directly() {
+    let (mut svm, fee_payer) = setup();
+
+    let tx = build_say_hello_tx(&svm, &fee_payer);
+
+    let result = svm.send_transaction(tx);
+    demand_event_emitted::<HelloEvent>(|e| e.program_id == simple_anchor_program::ID, result);
+}
+
+/// Test decoding an `emit!` event using the fluent trait method approach
+#[test]
+fn test_use_demand_event_emitted_fluently() {
+    let (mut svm, fee_payer) = setup();
+
+    let tx = build_say_hello_tx(&svm, &fee_payer);
+
+    svm.send_transaction(tx)
+        .demand_event_emitted_and::<HelloEvent>(|e| e.program_id == simple_anchor_program::ID)
+        .demand_logs_contain_and("Hello from anchor!")
+        .accept();
+}
+
+/// Test that a failing instruction emits no event
+#[test]
+fn test_use_demand_no_event() {
+    let (mut svm, fee_payer) = setup();
+
+    let tx = build_fail_instruction_tx(&svm, &fee_payer);
+
+    svm.send_transaction(tx).demand_no_event::<HelloEvent>();
+}
+
+/// Test a program-defined `#[error_code]` using the direct function call approach
+#[test]
+fn test_use_demand_anchor_error_directly() {
+    let (mut svm, fee_payer) = setup();
+
+    let tx = build_fail_instruction_tx(&svm, &fee_payer);
+
+    let result = svm.send_transaction(tx);
+    demand_anchor_error(SimpleAnchorError::AlwaysFails, result);
+}
+
+/// Test a program-defined `#[error_code]` using the fluent trait method approach
+#[test]
+fn test_use_demand_anchor_error_fluently() {
+    let (mut svm, fee_payer) = setup();
+
+    let tx = build_fail_instruction_tx(&svm, &fee_payer);
+
+    svm.send_transaction(tx)
+        .demand_anchor_error_at_index(0, SimpleAnchorError::AlwaysFails);
+}
+
 // Test utilities:
 
 const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
@@ -73,3 +130,20 @@ fn build_say_hello_tx(svm: &LiteSVM, fee_payer: &Keypair) -> Transaction {
         svm.latest_blockhash(),
     )
 }
+
+fn build_fail_instruction_tx(svm: &LiteSVM, fee_payer: &Keypair) -> Transaction {
+    let ix_accounts = simple_anchor_program::accounts::FailInstruction {};
+    let ix_data = simple_anchor_program::instruction::FailInstruction {};
+    let ix = Instruction {
+        program_id: simple_anchor_program::ID,
+        accounts: ix_accounts.to_account_metas(None),
+        data: ix_data.data(),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&fee_payer.pubkey()),
+        &[fee_payer],
+        svm.latest_blockhash(),
+    )
+}